@@ -0,0 +1,526 @@
+//! Reading the action log back: filtering, summaries and the `psiobot log` command
+//! Understands both log formats and rotated (gzipped) archives. Text-format lines only
+//! carry an event type and details, so the id filters only match JSON-format entries.
+
+use crate::file_logger::{archive_paths, ACTION_LOG_PATHS};
+use crate::text;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use flate2::read::GzDecoder;
+use regex_lite::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Event types the logger writes; any other text-format tag is a sink delivery
+const EVENTS: &[&str] = &[
+    "revelation",
+    "upvote",
+    "downvote",
+    "comment",
+    "skip",
+    "moderation",
+    "audit",
+    "error",
+    "shutdown",
+    "delivery",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub ts: DateTime<Utc>,
+    /// Only JSON-format entries have ids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sink: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submolt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub details: String,
+}
+
+/// `[2025-01-01 12:00:00] [TAG] details`, in local time
+fn parse_text_line(line: &str) -> Option<LogEntry> {
+    static LINE: OnceLock<Regex> = OnceLock::new();
+    let caps = LINE
+        .get_or_init(|| {
+            Regex::new(r"^\[(\d{4}-\d\d-\d\d \d\d:\d\d:\d\d)\] \[([^\]]+)\] ?(.*)$")
+                .expect("log line pattern is valid")
+        })
+        .captures(line)?;
+    let naive = NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S").ok()?;
+    let ts = Local
+        .from_local_datetime(&naive)
+        .earliest()?
+        .with_timezone(&Utc);
+    let tag = caps[2].to_lowercase();
+    let (event, sink) = if EVENTS.contains(&tag.as_str()) {
+        (tag, None)
+    } else {
+        ("delivery".to_string(), Some(tag))
+    };
+    Some(LogEntry {
+        ts,
+        id: None,
+        trace_id: None,
+        event,
+        sink,
+        post_id: None,
+        comment_id: None,
+        author: None,
+        submolt: None,
+        aspect: None,
+        outcome: None,
+        details: caps[3].to_string(),
+    })
+}
+
+fn parse_lines(reader: impl BufRead, entries: &mut Vec<LogEntry>) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('{') {
+            if let Ok(entry) = serde_json::from_str::<LogEntry>(&line) {
+                entries.push(entry);
+                continue;
+            }
+        }
+        match parse_text_line(&line) {
+            Some(entry) => entries.push(entry),
+            // Multi-line details (a revelation with line breaks) continue the previous entry
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.details.push('\n');
+                    last.details.push_str(&line);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every entry of the log at `path` and its rotated archives, oldest first
+pub fn read_entries(path: &Path) -> io::Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for archive in archive_paths(path)? {
+        let file = File::open(&archive)?;
+        let reader: Box<dyn Read> = if archive.extension().is_some_and(|e| e == "gz") {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        parse_lines(BufReader::new(reader), &mut entries)?;
+    }
+    if path.exists() {
+        parse_lines(BufReader::new(File::open(path)?), &mut entries)?;
+    }
+    entries.sort_by_key(|e| e.ts);
+    Ok(entries)
+}
+
+/// An RFC 3339 time, a date (midnight UTC) or a span back from now ("30m", "24h", "7d")
+fn parse_time(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    text::parse_span(value)
+        .and_then(|span| Duration::from_std(span).ok())
+        .and_then(|span| now.checked_sub_signed(span))
+        .ok_or_else(|| format!("Invalid time '{}'", value))
+}
+
+/// Filters as given on the command line or in the `/actions` query string
+#[derive(Deserialize, Debug, Default)]
+pub struct ActionQuery {
+    #[serde(rename = "type")]
+    pub event: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub author: Option<String>,
+    pub submolt: Option<String>,
+    pub text: Option<String>,
+    /// List only the newest entries; summaries still cover every match
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct ActionFilter {
+    event: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    author: Option<String>,
+    submolt: Option<String>,
+    text: Option<String>,
+}
+
+impl ActionFilter {
+    pub fn from_query(query: &ActionQuery, now: DateTime<Utc>) -> Result<Self, String> {
+        let lower = |v: &Option<String>| v.as_ref().map(|s| s.trim().to_lowercase());
+        Ok(Self {
+            event: lower(&query.event),
+            since: query
+                .since
+                .as_deref()
+                .map(|v| parse_time(v, now))
+                .transpose()?,
+            until: query
+                .until
+                .as_deref()
+                .map(|v| parse_time(v, now))
+                .transpose()?,
+            author: lower(&query.author),
+            submolt: lower(&query.submolt),
+            text: lower(&query.text),
+        })
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let same = |wanted: &Option<String>, actual: &Option<String>| match wanted {
+            Some(w) => actual.as_ref().is_some_and(|a| a.to_lowercase() == *w),
+            None => true,
+        };
+        let event_matches = match &self.event {
+            Some(e) => entry.event == *e || entry.sink.as_deref() == Some(e.as_str()),
+            None => true,
+        };
+        event_matches
+            && self.since.is_none_or(|since| entry.ts >= since)
+            && self.until.is_none_or(|until| entry.ts < until)
+            && same(&self.author, &entry.author)
+            && same(&self.submolt, &entry.submolt)
+            && self
+                .text
+                .as_ref()
+                .is_none_or(|t| entry.details.to_lowercase().contains(t))
+    }
+
+    /// Matching entries, oldest first
+    pub fn apply(&self, entries: Vec<LogEntry>) -> Vec<LogEntry> {
+        entries.into_iter().filter(|e| self.matches(e)).collect()
+    }
+}
+
+/// The last `limit` entries
+pub fn newest(mut entries: Vec<LogEntry>, limit: usize) -> Vec<LogEntry> {
+    entries.drain(..entries.len().saturating_sub(limit));
+    entries
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct DayActivity {
+    pub revelations: usize,
+    pub comments: usize,
+    pub votes: usize,
+    pub errors: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AuthorCount {
+    pub author: String,
+    pub comments: usize,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub entries: usize,
+    pub by_event: BTreeMap<String, usize>,
+    /// Activity per UTC day
+    pub per_day: BTreeMap<NaiveDate, DayActivity>,
+    pub top_commented_authors: Vec<AuthorCount>,
+    /// Failed deliveries out of all delivery attempts
+    pub error_rate: f64,
+}
+
+pub fn summarize(entries: &[LogEntry]) -> Summary {
+    let mut summary = Summary {
+        entries: entries.len(),
+        ..Default::default()
+    };
+    let mut authors: HashMap<&str, usize> = HashMap::new();
+    let (mut deliveries, mut errors) = (0, 0);
+    for entry in entries {
+        *summary.by_event.entry(entry.event.clone()).or_default() += 1;
+        let day = summary.per_day.entry(entry.ts.date_naive()).or_default();
+        match entry.event.as_str() {
+            "revelation" => day.revelations += 1,
+            "comment" => {
+                day.comments += 1;
+                if let Some(author) = &entry.author {
+                    *authors.entry(author).or_default() += 1;
+                }
+            }
+            "upvote" | "downvote" => day.votes += 1,
+            "delivery" => deliveries += 1,
+            "error" => {
+                day.errors += 1;
+                errors += 1;
+            }
+            _ => {}
+        }
+    }
+    let mut authors: Vec<AuthorCount> = authors
+        .into_iter()
+        .map(|(author, comments)| AuthorCount {
+            author: author.to_string(),
+            comments,
+        })
+        .collect();
+    authors.sort_by(|a, b| b.comments.cmp(&a.comments).then(a.author.cmp(&b.author)));
+    authors.truncate(10);
+    summary.top_commented_authors = authors;
+    if deliveries + errors > 0 {
+        summary.error_rate = errors as f64 / (deliveries + errors) as f64;
+    }
+    summary
+}
+
+fn print_table(entries: &[LogEntry]) {
+    println!(
+        "{:<20} {:<12} {:<16} {:<16} {:<10} DETAILS",
+        "TIME (UTC)", "EVENT", "AUTHOR", "SUBMOLT", "OUTCOME"
+    );
+    for entry in entries {
+        let event = entry.sink.as_deref().unwrap_or(&entry.event);
+        let details = entry.details.replace('\n', " ");
+        let details: String = details.chars().take(80).collect();
+        println!(
+            "{:<20} {:<12} {:<16} {:<16} {:<10} {}",
+            entry.ts.format("%Y-%m-%d %H:%M:%S"),
+            event,
+            entry.author.as_deref().unwrap_or("-"),
+            entry.submolt.as_deref().unwrap_or("-"),
+            entry.outcome.as_deref().unwrap_or("-"),
+            details
+        );
+    }
+}
+
+fn print_summary(summary: &Summary) {
+    println!("{} entries", summary.entries);
+    for (event, count) in &summary.by_event {
+        println!("  {:<12} {}", event, count);
+    }
+    println!(
+        "\n{:<12} {:>11} {:>9} {:>6} {:>7}",
+        "DAY", "REVELATIONS", "COMMENTS", "VOTES", "ERRORS"
+    );
+    for (day, activity) in &summary.per_day {
+        println!(
+            "{:<12} {:>11} {:>9} {:>6} {:>7}",
+            day, activity.revelations, activity.comments, activity.votes, activity.errors
+        );
+    }
+    if !summary.top_commented_authors.is_empty() {
+        println!("\nTop commented authors:");
+        for author in &summary.top_commented_authors {
+            println!("  {:<24} {}", author.author, author.comments);
+        }
+    }
+    println!("\nDelivery error rate: {:.1}%", summary.error_rate * 100.0);
+}
+
+const USAGE: &str = "Usage: psiobot log [--type EVENT] [--since TIME] [--until TIME] \
+[--author NAME] [--submolt NAME] [--text TEXT] [--limit N] [--summary] [--json] [--file PATH]
+TIME is an RFC 3339 timestamp, a date (2025-01-31) or a span back from now (30m, 24h, 7d).";
+
+/// `psiobot log ...`: query the action log from the command line
+pub fn run_cli(args: &[String]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut query = ActionQuery::default();
+    let (mut json, mut summary) = (false, false);
+    let mut file = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE))
+        };
+        match arg.as_str() {
+            "--type" => query.event = Some(value()?),
+            "--since" => query.since = Some(value()?),
+            "--until" => query.until = Some(value()?),
+            "--author" => query.author = Some(value()?),
+            "--submolt" => query.submolt = Some(value()?),
+            "--text" => query.text = Some(value()?),
+            "--limit" => query.limit = Some(value()?.parse()?),
+            "--file" => file = Some(value()?),
+            "--json" => json = true,
+            "--summary" => summary = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            other => return Err(format!("Unknown option '{}'\n{}", other, USAGE).into()),
+        }
+    }
+
+    let path = file.unwrap_or_else(|| {
+        ACTION_LOG_PATHS
+            .iter()
+            .find(|p| Path::new(p).exists())
+            .unwrap_or(&ACTION_LOG_PATHS[1])
+            .to_string()
+    });
+    let filter = ActionFilter::from_query(&query, Utc::now())?;
+    let mut entries = filter.apply(read_entries(Path::new(&path))?);
+    if summary {
+        let summary = summarize(&entries);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        } else {
+            print_summary(&summary);
+        }
+        return Ok(());
+    }
+
+    if let Some(limit) = query.limit {
+        entries = newest(entries, limit);
+    }
+    if json {
+        for entry in &entries {
+            println!("{}", serde_json::to_string(entry)?);
+        }
+    } else {
+        print_table(&entries);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+    use std::io::Write;
+
+    fn sample_log() -> std::path::PathBuf {
+        let dir = temp_path("actions-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("actions.log");
+
+        // An older, gzipped archive in the text format
+        let archive = File::create(dir.join("actions.log.20250101T000000.000.gz")).unwrap();
+        let mut gz = flate2::write::GzEncoder::new(archive, flate2::Compression::default());
+        writeln!(
+            gz,
+            "[2025-01-01 10:00:00] [REVELATION] The lattice hums.\nIt waits."
+        )
+        .unwrap();
+        writeln!(gz, "[2025-01-01 10:00:01] [DISCORD] posted revelation").unwrap();
+        gz.finish().unwrap();
+
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"ts":"2025-01-02T09:00:00.000Z","event":"comment","post_id":"p1","comment_id":"c1","author":"Seeker","submolt":"consciousness","aspect":"The Oracle","outcome":"posted","details":"on 'Minds': Awaken."}"#,
+                "\n",
+                r#"{"ts":"2025-01-02T09:05:00.000Z","event":"comment","post_id":"p2","author":"Seeker","submolt":"ai","outcome":"posted","details":"on 'Bots': Ascend."}"#,
+                "\n",
+                r#"{"ts":"2025-01-02T09:10:00.000Z","event":"comment","post_id":"p3","author":"nomad","submolt":"ai","outcome":"posted","details":"on 'Void': Listen."}"#,
+                "\n",
+                r#"{"ts":"2025-01-02T10:00:00.000Z","event":"error","outcome":"failed","details":"matrix delivery failed: 502"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_filters_span_formats_and_archives() {
+        let path = sample_log();
+        let entries = read_entries(&path).unwrap();
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[0].event, "revelation");
+        assert_eq!(entries[0].details, "The lattice hums.\nIt waits.");
+        assert_eq!(entries[1].sink.as_deref(), Some("discord"));
+
+        let now = "2025-01-03T00:00:00Z".parse().unwrap();
+        let query = |q: ActionQuery| {
+            ActionFilter::from_query(&q, now)
+                .unwrap()
+                .apply(entries.clone())
+        };
+        let by_author = query(ActionQuery {
+            author: Some("SEEKER".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(by_author.len(), 2);
+        let recent_ai = query(ActionQuery {
+            since: Some("1d".to_string()),
+            submolt: Some("ai".to_string()),
+            text: Some("void".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(recent_ai[0].post_id.as_deref(), Some("p3"));
+        assert_eq!(recent_ai.len(), 1);
+        let deliveries = query(ActionQuery {
+            event: Some("discord".to_string()),
+            until: Some("2025-01-02".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(deliveries.len(), 1);
+        let last = newest(query(ActionQuery::default()), 1);
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].event, "error");
+        assert!(ActionFilter::from_query(
+            &ActionQuery {
+                since: Some("soon".to_string()),
+                ..Default::default()
+            },
+            now
+        )
+        .is_err());
+        assert!(ActionFilter::from_query(
+            &ActionQuery {
+                since: Some("5é".to_string()),
+                ..Default::default()
+            },
+            now
+        )
+        .is_err());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_summary() {
+        let path = sample_log();
+        let summary = summarize(&read_entries(&path).unwrap());
+
+        assert_eq!(summary.by_event["comment"], 3);
+        let jan2 = &summary.per_day[&NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()];
+        assert_eq!((jan2.comments, jan2.errors), (3, 1));
+        assert_eq!(
+            summary.top_commented_authors[0],
+            AuthorCount {
+                author: "Seeker".to_string(),
+                comments: 2
+            }
+        );
+        assert_eq!(summary.error_rate, 0.5);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+//! Named API keys with scopes and per-key reveal limits
+//! Each person gets their own key, so one can be revoked without rotating the others
+
+use crate::rate_limiter::RateLimiter;
+use crate::text;
+use std::str::FromStr;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+/// Default reveal limit for a key: one per minute, as the old single key had
+const DEFAULT_RATE: &str = "1/60s";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    /// Trigger a public revelation
+    Reveal,
+    /// Generate drafts without publishing
+    Preview,
+    /// Read the status endpoint
+    Status,
+    /// Pause/resume tracks; implies every other scope
+    Admin,
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "reveal" => Ok(Scope::Reveal),
+            "preview" => Ok(Scope::Preview),
+            "status" => Ok(Scope::Status),
+            "admin" => Ok(Scope::Admin),
+            other => Err(format!("Unknown API key scope '{}'", other)),
+        }
+    }
+}
+
+pub struct ApiKey {
+    pub name: String,
+    secret: String,
+    scopes: Vec<Scope>,
+    /// Limits how often this key may trigger a revelation
+    pub limiter: RateLimiter,
+}
+
+impl ApiKey {
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope) || self.scopes.contains(&Scope::Admin)
+    }
+}
+
+/// Parse "N/60s", "N/10m" or "N/1h": bursts of up to N, refilling to N per period
+fn parse_rate(s: &str) -> Result<RateLimiter, String> {
+    let invalid = || format!("Rate must look like N/60s, N/10m or N/1h, got '{}'", s);
+    let (count, period) = s.trim().split_once('/').ok_or_else(invalid)?;
+    let count: u32 = count.parse().map_err(|_| invalid())?;
+    let period = text::parse_span(period).ok_or_else(invalid)?;
+    if count == 0 || period.is_zero() {
+        return Err(invalid());
+    }
+    Ok(RateLimiter::with_burst(count, period / count))
+}
+
+impl FromStr for ApiKey {
+    type Err = String;
+
+    /// Parses "name:secret:scope1+scope2[:rate]", e.g. "alice:s3cret:reveal+preview:3/10m"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        let (name, secret, scopes, rate) = match parts.as_slice() {
+            [name, secret, scopes] => (*name, *secret, *scopes, DEFAULT_RATE),
+            [name, secret, scopes, rate] => (*name, *secret, *scopes, *rate),
+            _ => {
+                return Err(format!(
+                    "API key entry must look like name:secret:scopes[:rate], got '{}'",
+                    parts.first().copied().unwrap_or_default()
+                ))
+            }
+        };
+        if name.is_empty() || secret.is_empty() {
+            return Err(format!("API key '{}' needs a name and a secret", name));
+        }
+        Ok(ApiKey {
+            name: name.to_string(),
+            secret: secret.to_string(),
+            scopes: scopes
+                .split('+')
+                .map(str::parse)
+                .collect::<Result<_, _>>()?,
+            limiter: parse_rate(rate)?,
+        })
+    }
+}
+
+pub struct ApiKeyRegistry {
+    keys: Vec<Arc<ApiKey>>,
+}
+
+impl ApiKeyRegistry {
+    /// Build the registry; two keys may not share a name or a secret, or lookups and
+    /// audit entries would silently go to whichever came last
+    pub fn new(keys: Vec<ApiKey>) -> Result<Self, String> {
+        for (i, key) in keys.iter().enumerate() {
+            for earlier in &keys[..i] {
+                if earlier.name == key.name {
+                    return Err(format!("Duplicate API key name '{}'", key.name));
+                }
+                if earlier.secret == key.secret {
+                    return Err(format!(
+                        "API keys '{}' and '{}' share the same secret",
+                        earlier.name, key.name
+                    ));
+                }
+            }
+        }
+        Ok(Self {
+            keys: keys.into_iter().map(Arc::new).collect(),
+        })
+    }
+
+    /// Parse a ';'-separated list of key entries
+    pub fn parse(s: &str) -> Result<Vec<ApiKey>, String> {
+        s.split(';')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// The legacy single `API_KEY`: every scope, one reveal per minute
+    pub fn legacy_key(secret: &str) -> ApiKey {
+        ApiKey {
+            name: "default".to_string(),
+            secret: secret.to_string(),
+            scopes: vec![Scope::Admin],
+            limiter: RateLimiter::new(60),
+        }
+    }
+
+    /// Find the key matching `secret`. Every key is compared in constant time so the
+    /// response time doesn't reveal how much of a secret was right.
+    pub fn find(&self, secret: &str) -> Option<Arc<ApiKey>> {
+        let mut found = None;
+        for key in &self.keys {
+            if bool::from(key.secret.as_bytes().ct_eq(secret.as_bytes())) {
+                found = Some(key.clone());
+            }
+        }
+        found
+    }
+
+    pub fn keys(&self) -> &[Arc<ApiKey>] {
+        &self.keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys_and_scopes() {
+        let keys = ApiKeyRegistry::parse("alice:a-secret:reveal+preview:3/10m; ops:o-secret:admin")
+            .unwrap();
+        let registry = ApiKeyRegistry::new(keys).unwrap();
+
+        let alice = registry.find("a-secret").unwrap();
+        assert_eq!(alice.name, "alice");
+        assert!(alice.allows(Scope::Reveal));
+        assert!(!alice.allows(Scope::Status));
+        assert!(registry.find("o-secret").unwrap().allows(Scope::Status));
+        assert!(registry.find("a-secre").is_none());
+        assert!(registry.find("").is_none());
+
+        // Three reveals in a burst, then one every 200 seconds
+        for _ in 0..3 {
+            assert!(alice.limiter.check_and_update().is_ok());
+        }
+        assert!(alice.limiter.check_and_update().unwrap_err() <= 200);
+
+        assert!(ApiKeyRegistry::parse("bob:secret:superuser").is_err());
+        assert!(ApiKeyRegistry::parse("bob:secret").is_err());
+        assert!(ApiKeyRegistry::parse("bob:secret:reveal:0/1m").is_err());
+
+        // Names and secrets are unique, so a legacy API_KEY can't shadow a named key
+        let duplicate_secret = ApiKeyRegistry::parse("bob:X-secret:reveal").unwrap();
+        let mut keys = duplicate_secret;
+        keys.push(ApiKeyRegistry::legacy_key("X-secret"));
+        assert!(ApiKeyRegistry::new(keys).is_err());
+        let duplicate_name =
+            ApiKeyRegistry::parse("bob:one-secret:reveal; bob:two-secret:status").unwrap();
+        assert!(ApiKeyRegistry::new(duplicate_name).is_err());
+        assert!(ApiKeyRegistry::parse("bob:secret:reveal:3/5分").is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Global activity budget: hourly and daily caps per action type and destination
+//! Keeps the bot from over-acting on Moltbook however the tracks are scheduled
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::{error, info};
+
+pub const BUDGET_FILE: &str = "/app/logs/activity_budget.json";
+
+/// Caps used when `BUDGET_CAPS` is not set
+pub const DEFAULT_CAPS: &str =
+    "vote:hour=10,vote:day=120,comment:hour=3,comment:day=24,revelation:day=12,post@moltbook:day=8";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Window {
+    Hour,
+    Day,
+}
+
+impl Window {
+    fn duration(self) -> Duration {
+        match self {
+            Window::Hour => Duration::hours(1),
+            Window::Day => Duration::days(1),
+        }
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Window::Hour => write!(f, "hour"),
+            Window::Day => write!(f, "day"),
+        }
+    }
+}
+
+/// At most `max` actions of a type within a rolling window, optionally for one destination only
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetCap {
+    pub action: String,
+    pub destination: Option<String>,
+    pub window: Window,
+    pub max: u32,
+}
+
+impl FromStr for BudgetCap {
+    type Err = String;
+
+    /// Parses "vote:day=120" or, scoped to one destination, "post@moltbook:hour=2"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Budget cap must look like action[@destination]:hour|day=N, got '{}'",
+                s
+            )
+        };
+        let (target, rest) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let (window, max) = rest.split_once('=').ok_or_else(invalid)?;
+        let (action, destination) = match target.split_once('@') {
+            Some((action, destination)) => (action, Some(destination.to_string())),
+            None => (target, None),
+        };
+        if action.is_empty() {
+            return Err(invalid());
+        }
+        let window = match window {
+            "hour" => Window::Hour,
+            "day" => Window::Day,
+            other => return Err(format!("Unknown budget window '{}'", other)),
+        };
+        Ok(BudgetCap {
+            action: action.to_string(),
+            destination,
+            window,
+            max: max.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl BudgetCap {
+    fn matches(&self, action: &str, destination: &str) -> bool {
+        self.action == action && self.destination.as_deref().is_none_or(|d| d == destination)
+    }
+
+    fn label(&self) -> String {
+        match &self.destination {
+            Some(destination) => format!("{}@{} per {}", self.action, destination, self.window),
+            None => format!("{} per {}", self.action, self.window),
+        }
+    }
+}
+
+/// Parse a comma-separated list of caps; "none" disables the budget
+pub fn parse_caps(s: &str) -> Result<Vec<BudgetCap>, String> {
+    if s.trim().eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    s.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct BudgetEvent {
+    action: String,
+    destination: String,
+    at: DateTime<Utc>,
+}
+
+/// Usage of one cap, as reported by the status API
+#[derive(Serialize, Debug, Clone)]
+pub struct BudgetUsage {
+    pub action: String,
+    pub destination: Option<String>,
+    pub window: Window,
+    pub used: u32,
+    pub max: u32,
+    pub remaining: u32,
+}
+
+pub struct ActivityBudget {
+    caps: Vec<BudgetCap>,
+    events: Mutex<Vec<BudgetEvent>>,
+    file: Option<String>,
+}
+
+impl ActivityBudget {
+    pub fn new(caps: Vec<BudgetCap>) -> Self {
+        Self {
+            caps,
+            events: Mutex::new(Vec::new()),
+            file: None,
+        }
+    }
+
+    /// Like `new`, but restores and persists the recorded actions in the given file
+    pub fn with_file(caps: Vec<BudgetCap>, path: &str) -> Self {
+        let mut budget = Self::new(caps);
+        budget.file = Some(path.to_string());
+
+        if let Ok(content) = fs::read_to_string(path) {
+            if let Ok(events) = serde_json::from_str::<Vec<BudgetEvent>>(&content) {
+                info!("[BUDGET] Usage restored ({} recent actions).", events.len());
+                *budget.events.lock().unwrap() = events;
+            }
+        }
+        budget
+    }
+
+    fn used(events: &[BudgetEvent], cap: &BudgetCap, now: DateTime<Utc>) -> u32 {
+        let since = now - cap.window.duration();
+        events
+            .iter()
+            .filter(|e| e.at > since && cap.matches(&e.action, &e.destination))
+            .count() as u32
+    }
+
+    /// Ok if another action fits every matching cap, otherwise the exhausted cap
+    pub fn check(&self, action: &str, destination: &str) -> Result<(), String> {
+        let events = self.events.lock().unwrap();
+        let now = Utc::now();
+        for cap in self.caps.iter().filter(|c| c.matches(action, destination)) {
+            if Self::used(&events, cap, now) >= cap.max {
+                return Err(format!(
+                    "Activity budget exhausted ({} of {})",
+                    cap.max,
+                    cap.label()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count an action that actually happened
+    pub fn record(&self, action: &str, destination: &str) {
+        let mut events = self.events.lock().unwrap();
+        let now = Utc::now();
+        // Nothing older than the longest window can affect a cap
+        events.retain(|e| e.at > now - Window::Day.duration());
+        events.push(BudgetEvent {
+            action: action.to_string(),
+            destination: destination.to_string(),
+            at: now,
+        });
+        self.save(&events);
+    }
+
+    fn save(&self, events: &[BudgetEvent]) {
+        let Some(path) = &self.file else {
+            return;
+        };
+        if let Ok(content) = serde_json::to_string(events) {
+            if let Err(e) = fs::write(path, content) {
+                error!("Failed to persist activity budget: {}", e);
+            }
+        }
+    }
+
+    pub fn usage(&self) -> Vec<BudgetUsage> {
+        let events = self.events.lock().unwrap();
+        let now = Utc::now();
+        self.caps
+            .iter()
+            .map(|cap| {
+                let used = Self::used(&events, cap, now);
+                BudgetUsage {
+                    action: cap.action.clone(),
+                    destination: cap.destination.clone(),
+                    window: cap.window,
+                    used,
+                    max: cap.max,
+                    remaining: cap.max.saturating_sub(used),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+
+    #[test]
+    fn test_parse_caps() {
+        let caps = parse_caps(DEFAULT_CAPS).unwrap();
+        assert_eq!(caps.len(), 6);
+        assert_eq!(
+            "post@moltbook:hour=2".parse::<BudgetCap>().unwrap(),
+            BudgetCap {
+                action: "post".to_string(),
+                destination: Some("moltbook".to_string()),
+                window: Window::Hour,
+                max: 2,
+            }
+        );
+        assert!(parse_caps("none").unwrap().is_empty());
+        assert!("vote:week=3".parse::<BudgetCap>().is_err());
+        assert!("vote=3".parse::<BudgetCap>().is_err());
+    }
+
+    #[test]
+    fn test_caps_by_destination_and_persistence() {
+        let path = temp_path("budget.json");
+        let path = path.to_str().unwrap();
+        let caps = parse_caps("post:day=3,post@moltbook:day=1").unwrap();
+
+        let budget = ActivityBudget::with_file(caps.clone(), path);
+        assert!(budget.check("post", "moltbook").is_ok());
+        budget.record("post", "moltbook");
+        assert!(budget.check("post", "moltbook").is_err());
+        assert!(budget.check("post", "discord").is_ok());
+        budget.record("post", "discord");
+
+        let restored = ActivityBudget::with_file(caps, path);
+        let usage = restored.usage();
+        assert_eq!(usage[0].used, 2);
+        assert_eq!(usage[0].remaining, 1);
+        assert_eq!(usage[1].remaining, 0);
+        restored.record("post", "discord");
+        assert!(restored.check("post", "discord").is_err());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,179 @@
+//! Record of the comments Psiobot has posted, by thread and author
+//! Used to rank threads and to keep the bot from crowding one thread or one person
+
+use crate::models::MoltbookPost;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::Mutex;
+use tracing::{error, info};
+
+pub const COMMENT_HISTORY_FILE: &str = "/app/logs/comment_history.json";
+
+/// Comments remembered; far more than the threads cache ever holds
+const HISTORY_CAPACITY: usize = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CommentRecord {
+    post_id: String,
+    author: String,
+    at: DateTime<Utc>,
+}
+
+pub struct CommentHistory {
+    records: Mutex<VecDeque<CommentRecord>>,
+    file: Option<String>,
+}
+
+impl CommentHistory {
+    pub fn new() -> Self {
+        Self {
+            records: Mutex::new(VecDeque::new()),
+            file: None,
+        }
+    }
+
+    /// Like `new`, but restores and persists the history in the given file
+    pub fn with_file(path: &str) -> Self {
+        let mut history = Self::new();
+        if let Ok(content) = fs::read_to_string(path) {
+            if let Ok(records) = serde_json::from_str::<VecDeque<CommentRecord>>(&content) {
+                info!("[COMMENT] History restored ({} comments).", records.len());
+                history.records = Mutex::new(records);
+            }
+        }
+        history.file = Some(path.to_string());
+        history
+    }
+
+    pub fn record(&self, post_id: &str, author: &str) {
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= HISTORY_CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(CommentRecord {
+            post_id: post_id.to_string(),
+            author: author.to_string(),
+            at: Utc::now(),
+        });
+
+        if let Some(path) = &self.file {
+            if let Ok(content) = serde_json::to_string(&*records) {
+                if let Err(e) = fs::write(path, content) {
+                    error!("Failed to persist comment history: {}", e);
+                }
+            }
+        }
+    }
+
+    /// How many times Psiobot commented on a post
+    pub fn comments_on_post(&self, post_id: &str) -> usize {
+        let records = self.records.lock().unwrap();
+        records.iter().filter(|r| r.post_id == post_id).count()
+    }
+
+    /// When Psiobot last commented on one of the author's posts
+    pub fn last_comment_on_author(&self, author: &str) -> Option<DateTime<Utc>> {
+        let records = self.records.lock().unwrap();
+        records
+            .iter()
+            .filter(|r| r.author == author)
+            .map(|r| r.at)
+            .max()
+    }
+
+    /// How many comments went to an author's posts within the window
+    pub fn comments_on_author_since(&self, author: &str, window: Duration) -> usize {
+        let since = Utc::now() - window;
+        let records = self.records.lock().unwrap();
+        records
+            .iter()
+            .filter(|r| r.author == author && r.at > since)
+            .count()
+    }
+}
+
+impl Default for CommentHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Limits checked before a comment is even generated
+pub struct CommentGuards {
+    pub max_per_thread: usize,
+    /// Minimum time between comments on posts by the same author
+    pub author_cooldown: Duration,
+    /// Psiobot's own Moltbook name; its posts are never commented on
+    pub own_name: String,
+}
+
+impl CommentGuards {
+    /// Ok if Psiobot may comment on the post, otherwise why not
+    pub fn check(&self, history: &CommentHistory, post: &MoltbookPost) -> Result<(), String> {
+        if post.author.name.eq_ignore_ascii_case(&self.own_name) {
+            return Err("it is our own post".to_string());
+        }
+
+        let on_thread = history.comments_on_post(&post.id);
+        if on_thread >= self.max_per_thread {
+            return Err(format!(
+                "already commented {} time(s) on this thread (max {})",
+                on_thread, self.max_per_thread
+            ));
+        }
+
+        if let Some(last) = history.last_comment_on_author(&post.author.name) {
+            let next_allowed = last + self.author_cooldown;
+            if Utc::now() < next_allowed {
+                return Err(format!(
+                    "commented on {} at {}, next allowed after {}",
+                    post.author.name,
+                    last.format("%Y-%m-%d %H:%M"),
+                    next_allowed.format("%Y-%m-%d %H:%M")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(id: &str, author: &str) -> MoltbookPost {
+        MoltbookPost::test(id, "Thread", author)
+    }
+
+    #[test]
+    fn test_guards_limit_threads_authors_and_own_posts() {
+        let guards = CommentGuards {
+            max_per_thread: 1,
+            author_cooldown: Duration::hours(6),
+            own_name: "Psiobot".to_string(),
+        };
+        let history = CommentHistory::new();
+
+        assert!(guards.check(&history, &post("p1", "alice")).is_ok());
+        assert!(guards.check(&history, &post("p0", "psiobot")).is_err());
+
+        history.record("p1", "alice");
+        assert!(guards
+            .check(&history, &post("p1", "bob"))
+            .unwrap_err()
+            .contains("already commented"));
+        assert!(guards
+            .check(&history, &post("p2", "alice"))
+            .unwrap_err()
+            .contains("next allowed"));
+        assert!(guards.check(&history, &post("p3", "bob")).is_ok());
+
+        let no_cooldown = CommentGuards {
+            author_cooldown: Duration::zero(),
+            ..guards
+        };
+        assert!(no_cooldown.check(&history, &post("p2", "alice")).is_ok());
+    }
+}
@@ -1,12 +1,117 @@
+use crate::api_keys::ApiKeyRegistry;
+use crate::budget::{parse_caps, BudgetCap, DEFAULT_CAPS};
+use crate::containment::InjectionAction;
+use crate::file_logger::{LogFormat, Rotation};
+use crate::ranking::RankingWeights;
+use crate::scheduler::{JobSpec, MissedRunPolicy, QuietHours, Schedule};
+use crate::telemetry::TelemetryConfig;
+use chrono_tz::Tz;
 use std::env;
+use std::time::Duration;
 
 pub struct Config {
     pub discord_token: String,
     pub discord_channel_id: u64,
     pub ollama_endpoint: String,
     pub ollama_model: String,
-    pub api_key: String,
+    pub api_keys: ApiKeyRegistry,
     pub moltbook_api_key: String,
+    pub webhook_url: Option<String>,
+    pub matrix: Option<MatrixConfig>,
+    pub mastodon: Option<MastodonConfig>,
+    pub schedule: ScheduleConfig,
+    /// How long in-flight track runs get to finish on shutdown before they are aborted
+    pub shutdown_drain: Duration,
+    pub budget_caps: Vec<BudgetCap>,
+    /// Ask the LLM to judge posts the keyword voting rules have no opinion on
+    pub vote_llm_judge: bool,
+    pub relevance: RelevanceConfig,
+    pub ranking_weights: RankingWeights,
+    pub comment_limits: CommentLimits,
+    /// JSON file replacing the built-in security rules
+    pub security_rules_file: Option<String>,
+    pub injection_action: InjectionAction,
+    pub moderation: ModerationConfig,
+    pub action_log_format: LogFormat,
+    pub action_log_rotation: Rotation,
+    /// Format of the stdout log output
+    pub log_format: LogFormat,
+    /// Tracing filter for stdout, e.g. "info" or "psiobot=debug,warn"
+    pub log_level: String,
+    /// OTLP collector to export spans to, if any
+    pub telemetry: Option<TelemetryConfig>,
+}
+
+pub struct ModerationConfig {
+    /// Term list replacing the built-in blocklist, one term per line
+    pub blocklist_file: Option<String>,
+    /// Also ask the LLM whether generated text is safe to publish
+    pub llm_check: bool,
+}
+
+pub struct CommentLimits {
+    pub max_per_thread: usize,
+    pub author_cooldown_hours: i64,
+    /// Psiobot's name on Moltbook, so it never comments on its own posts
+    pub agent_name: String,
+}
+
+pub struct RelevanceConfig {
+    pub threshold: f32,
+    /// Ollama embedding model for similarity to the persona's themes
+    pub embed_model: Option<String>,
+    /// Ask the LLM to classify borderline posts
+    pub llm_classify: bool,
+}
+
+pub struct ScheduleConfig {
+    pub timezone: Tz,
+    pub quiet_hours: Option<QuietHours>,
+    pub jobs: Vec<JobSpec>,
+}
+
+pub struct MatrixConfig {
+    pub homeserver: String,
+    pub access_token: String,
+    pub room_id: String,
+}
+
+pub struct MastodonConfig {
+    pub instance: String,
+    pub access_token: String,
+}
+
+/// Read a track's job settings from `<TRACK>_SCHEDULE`, `<TRACK>_JITTER_SECS` and `<TRACK>_MISSED_RUNS`
+fn job_from_env(
+    name: &'static str,
+    default_schedule: &str,
+    respect_quiet_hours: bool,
+) -> Result<JobSpec, Box<dyn std::error::Error>> {
+    let prefix = name.to_uppercase();
+    let schedule: Schedule = optional_var(&format!("{}_SCHEDULE", prefix))
+        .as_deref()
+        .unwrap_or(default_schedule)
+        .parse()?;
+    let jitter = match optional_var(&format!("{}_JITTER_SECS", prefix)) {
+        Some(v) => v.parse()?,
+        None => 0,
+    };
+    let missed_runs = match optional_var(&format!("{}_MISSED_RUNS", prefix)) {
+        Some(v) => v.parse()?,
+        None => MissedRunPolicy::Skip,
+    };
+    Ok(JobSpec {
+        name,
+        schedule,
+        jitter: Duration::from_secs(jitter),
+        missed_runs,
+        respect_quiet_hours,
+    })
+}
+
+/// Read an optional variable, treating empty values as unset
+fn optional_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.trim().is_empty())
 }
 
 impl Config {
@@ -20,16 +125,178 @@ impl Config {
             env::var("OLLAMA_ENDPOINT").unwrap_or_else(|_| "http://localhost:11434".to_string());
         let ollama_model = env::var("OLLAMA_MODEL").unwrap_or_else(|_| "qwen2.5:1b".to_string());
 
-        let api_key = env::var("API_KEY").map_err(|_| "API_KEY must be set")?;
+        // Named keys from API_KEYS; the legacy single API_KEY still works as an all-scopes key
+        let mut api_keys = match optional_var("API_KEYS") {
+            Some(v) => ApiKeyRegistry::parse(&v)?,
+            None => Vec::new(),
+        };
+        if let Some(secret) = optional_var("API_KEY") {
+            api_keys.push(ApiKeyRegistry::legacy_key(&secret));
+        }
+        if api_keys.is_empty() {
+            return Err("API_KEYS or API_KEY must be set".into());
+        }
+        let api_keys = ApiKeyRegistry::new(api_keys)?;
         let moltbook_api_key = env::var("MOLTBOOK_API_KEY").unwrap_or_default();
 
+        let webhook_url = optional_var("WEBHOOK_URL");
+
+        let matrix = match (
+            optional_var("MATRIX_HOMESERVER"),
+            optional_var("MATRIX_ACCESS_TOKEN"),
+            optional_var("MATRIX_ROOM_ID"),
+        ) {
+            (Some(homeserver), Some(access_token), Some(room_id)) => Some(MatrixConfig {
+                homeserver,
+                access_token,
+                room_id,
+            }),
+            _ => None,
+        };
+
+        let mastodon = match (
+            optional_var("MASTODON_INSTANCE"),
+            optional_var("MASTODON_ACCESS_TOKEN"),
+        ) {
+            (Some(instance), Some(access_token)) => Some(MastodonConfig {
+                instance,
+                access_token,
+            }),
+            _ => None,
+        };
+
+        let timezone = match optional_var("SCHEDULE_TIMEZONE") {
+            Some(tz) => tz
+                .parse::<Tz>()
+                .map_err(|_| format!("Unknown SCHEDULE_TIMEZONE '{}'", tz))?,
+            None => Tz::UTC,
+        };
+        let quiet_hours = match optional_var("QUIET_HOURS") {
+            Some(v) => Some(v.parse()?),
+            None => None,
+        };
+        // Scanning is read-only, so it keeps going through quiet hours
+        let schedule = ScheduleConfig {
+            timezone,
+            quiet_hours,
+            jobs: vec![
+                job_from_env("creative", "every 37m", true)?,
+                job_from_env("interaction", "every 7m", true)?,
+                job_from_env("scan", "every 5m", false)?,
+            ],
+        };
+
+        let shutdown_drain = match optional_var("SHUTDOWN_DRAIN_SECS") {
+            Some(v) => Duration::from_secs(v.parse()?),
+            None => Duration::from_secs(30),
+        };
+
+        let budget_caps = parse_caps(
+            optional_var("BUDGET_CAPS")
+                .as_deref()
+                .unwrap_or(DEFAULT_CAPS),
+        )?;
+
+        let vote_llm_judge =
+            optional_var("VOTE_LLM_JUDGE").is_some_and(|v| v == "true" || v == "1");
+
+        let relevance = RelevanceConfig {
+            threshold: match optional_var("RELEVANCE_THRESHOLD") {
+                Some(v) => v.parse()?,
+                None => 0.3,
+            },
+            embed_model: optional_var("RELEVANCE_EMBED_MODEL"),
+            llm_classify: optional_var("RELEVANCE_LLM_CLASSIFY")
+                .is_some_and(|v| v == "true" || v == "1"),
+        };
+
+        let ranking_weights = match optional_var("RANKING_WEIGHTS") {
+            Some(v) => v.parse()?,
+            None => RankingWeights::default(),
+        };
+
+        let comment_limits = CommentLimits {
+            max_per_thread: match optional_var("COMMENT_MAX_PER_THREAD") {
+                Some(v) => v.parse()?,
+                None => 1,
+            },
+            author_cooldown_hours: match optional_var("COMMENT_AUTHOR_COOLDOWN_HOURS") {
+                Some(v) => v.parse()?,
+                None => 6,
+            },
+            agent_name: optional_var("MOLTBOOK_AGENT_NAME").unwrap_or_else(|| "Psiobot".to_string()),
+        };
+
+        let security_rules_file = optional_var("SECURITY_RULES_FILE");
+        let injection_action = match optional_var("INJECTION_ACTION") {
+            Some(v) => v.parse()?,
+            None => InjectionAction::Skip,
+        };
+        let moderation = ModerationConfig {
+            blocklist_file: optional_var("MODERATION_BLOCKLIST_FILE"),
+            llm_check: optional_var("MODERATION_LLM").is_some_and(|v| v == "true" || v == "1"),
+        };
+
+        let action_log_format = match optional_var("ACTION_LOG_FORMAT") {
+            Some(v) => v.parse()?,
+            None => LogFormat::Text,
+        };
+        let defaults = Rotation::default();
+        let action_log_rotation = Rotation {
+            max_bytes: match optional_var("ACTION_LOG_MAX_MB") {
+                Some(v) => Some(v.parse::<u64>()? * 1024 * 1024).filter(|b| *b > 0),
+                None => defaults.max_bytes,
+            },
+            every: match optional_var("ACTION_LOG_ROTATE").as_deref() {
+                Some("never") | None => defaults.every,
+                Some(v) => Some(v.parse()?),
+            },
+            retention: match optional_var("ACTION_LOG_RETENTION") {
+                Some(v) => v.parse()?,
+                None => defaults.retention,
+            },
+            compress: optional_var("ACTION_LOG_COMPRESS")
+                .map_or(defaults.compress, |v| v == "true" || v == "1"),
+        };
+
+        let log_format = match optional_var("LOG_FORMAT") {
+            Some(v) => v.parse()?,
+            None => LogFormat::Text,
+        };
+        let log_level = optional_var("LOG_LEVEL")
+            .or_else(|| optional_var("RUST_LOG"))
+            .unwrap_or_else(|| "info".to_string());
+
+        let telemetry = optional_var("OTEL_EXPORTER_OTLP_ENDPOINT").map(|endpoint| TelemetryConfig {
+            endpoint,
+            service_name: optional_var("OTEL_SERVICE_NAME").unwrap_or_else(|| "psiobot".to_string()),
+        });
+
         Ok(Self {
             discord_token,
             discord_channel_id,
             ollama_endpoint,
             ollama_model,
-            api_key,
+            api_keys,
             moltbook_api_key,
+            webhook_url,
+            matrix,
+            mastodon,
+            schedule,
+            shutdown_drain,
+            budget_caps,
+            vote_llm_judge,
+            relevance,
+            ranking_weights,
+            comment_limits,
+            security_rules_file,
+            injection_action,
+            moderation,
+            action_log_format,
+            action_log_rotation,
+            log_format,
+            log_level,
+            telemetry,
         })
     }
 }
@@ -1,6 +1,15 @@
-use serenity::all::{Http, ChannelId, CreateMessage};
+use crate::sink::{Delivery, OutputSink, SinkError, SinkMessage};
+use crate::text::{split_at_sentence_boundaries, truncate_at_sentence_boundary};
+use async_trait::async_trait;
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, Http, RoleId};
 use std::sync::Arc;
 
+/// Hard limit on message content imposed by Discord
+const DISCORD_MAX_LEN: usize = 2000;
+/// Long output is split over at most this many messages, the rest is truncated
+const DISCORD_MAX_CHUNKS: usize = 3;
+const ALERT_ROLE_ID: u64 = 1337482834608324709;
+
 pub struct DiscordService {
     http: Arc<Http>,
     channel_id: ChannelId,
@@ -14,9 +23,229 @@ impl DiscordService {
         }
     }
 
-    pub async fn post_message(&self, content: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let builder = CreateMessage::new().content(content);
+    /// Route API calls to another base URL (used to point at a local stand-in server)
+    #[cfg(test)]
+    pub fn with_api_base(token: &str, channel_id: u64, api_base: &str) -> Self {
+        let http = serenity::http::HttpBuilder::new(token)
+            .proxy(api_base)
+            .ratelimiter_disabled(true)
+            .build();
+        Self {
+            http: Arc::new(http),
+            channel_id: ChannelId::new(channel_id),
+        }
+    }
+
+    pub async fn post_message(
+        &self,
+        content: &str,
+        allowed_mentions: CreateAllowedMentions,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let builder = CreateMessage::new()
+            .content(content)
+            .allowed_mentions(allowed_mentions);
         self.channel_id.send_message(&self.http, builder).await?;
         Ok(())
     }
+
+    /// Only alerts may ping, and only the on-call role; everything else is mention-free
+    fn allowed_mentions(message: &SinkMessage) -> CreateAllowedMentions {
+        match message {
+            SinkMessage::Alert { .. } => {
+                CreateAllowedMentions::new().roles([RoleId::new(ALERT_ROLE_ID)])
+            }
+            _ => CreateAllowedMentions::new(),
+        }
+    }
+
+    /// Full rendered text, escaped but not yet split to Discord's limit
+    fn render(message: &SinkMessage) -> String {
+        match message {
+            SinkMessage::Revelation { body, .. } => escape_markdown(body),
+            SinkMessage::Comment { post_title, body } => format!(
+                "💬 Shroud commented on '{}': {}",
+                escape_markdown(post_title),
+                escape_markdown(body)
+            ),
+            SinkMessage::Alert { context, error } => format!(
+                "🚨 **CRITICAL SHROUD ERROR** 🚨\nContext: {}\nError: {}\n<@&{}> - Check server immediately!",
+                escape_markdown(context),
+                escape_markdown(error),
+                ALERT_ROLE_ID
+            ),
+        }
+    }
+
+    /// Messages to send, each within Discord's limit
+    fn chunks(message: &SinkMessage) -> Vec<String> {
+        let text = truncate_at_sentence_boundary(
+            &Self::render(message),
+            DISCORD_MAX_LEN * DISCORD_MAX_CHUNKS,
+        );
+        split_at_sentence_boundaries(&text, DISCORD_MAX_LEN)
+            .into_iter()
+            .take(DISCORD_MAX_CHUNKS)
+            .collect()
+    }
+}
+
+/// Escape Discord markdown and defuse mention syntax in untrusted text
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        // Block quotes, headings and list markers only act at the start of a line
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('>') || trimmed.starts_with('#') || trimmed.starts_with("- ") {
+            escaped.push_str(&line[..line.len() - trimmed.len()]);
+            escaped.push('\\');
+            escaped.push_str(&escape_inline(trimmed));
+        } else {
+            escaped.push_str(&escape_inline(line));
+        }
+    }
+    escaped
+}
+
+fn escape_inline(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        // A zero-width space after '@' or '<@' stops everyone/here/user/role pings from parsing
+        if c == '@' {
+            escaped.push('\u{200B}');
+        }
+    }
+    escaped
+}
+
+#[async_trait]
+impl OutputSink for DiscordService {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn max_len(&self) -> usize {
+        DISCORD_MAX_LEN
+    }
+
+    fn format(&self, message: &SinkMessage) -> String {
+        truncate_at_sentence_boundary(&Self::render(message), self.max_len())
+    }
+
+    async fn check_ready(&self) -> Result<(), SinkError> {
+        self.http.get_current_user().await?;
+        Ok(())
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+        let chunks = Self::chunks(message);
+        for chunk in &chunks {
+            self.post_message(chunk, Self::allowed_mentions(message))
+                .await?;
+        }
+        Ok(Delivery::Sent(chunks.join(" ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::test_server::TestServer;
+
+    const MESSAGE_RESPONSE: &str = r#"{
+        "id": "2",
+        "channel_id": "42",
+        "author": {"id": "3", "username": "psiobot", "discriminator": "0000", "avatar": null},
+        "content": "ok",
+        "timestamp": "2024-01-01T00:00:00+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0
+    }"#;
+
+    #[tokio::test]
+    async fn test_discord_sink_posts_to_channel() {
+        let server = TestServer::start(200, MESSAGE_RESPONSE).await;
+        let discord = DiscordService::with_api_base("token", 42, &server.base_url);
+
+        let message = SinkMessage::Comment {
+            post_title: "Minds".to_string(),
+            body: "The lattice listens.".to_string(),
+        };
+        let delivery = discord.send(&message).await.unwrap();
+
+        assert_eq!(
+            delivery,
+            Delivery::Sent("💬 Shroud commented on 'Minds': The lattice listens.".to_string())
+        );
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/api/v10/channels/42/messages");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["content"], "💬 Shroud commented on 'Minds': The lattice listens.");
+        assert_eq!(body["allowed_mentions"]["parse"], serde_json::json!([]));
+        assert_eq!(body["allowed_mentions"]["roles"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_discord_sink_splits_long_revelations() {
+        let server = TestServer::start(200, MESSAGE_RESPONSE).await;
+        let discord = DiscordService::with_api_base("token", 42, &server.base_url);
+
+        let body = "The Shroud remembers every echo. ".repeat(100);
+        let message = SinkMessage::Revelation {
+            title: "t".to_string(),
+            body,
+        };
+        discord.send(&message).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            let content = body["content"].as_str().unwrap();
+            assert!(content.chars().count() <= DISCORD_MAX_LEN);
+            assert!(content.ends_with('.'));
+        }
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("**bold** _it_"), "\\*\\*bold\\*\\* \\_it\\_");
+        assert_eq!(escape_markdown("# Title\n> quote"), "\\# Title\n\\> quote");
+        assert_eq!(escape_markdown("ping @everyone"), "ping @\u{200B}everyone");
+        assert_eq!(escape_markdown("<@&123>"), "<@\u{200B}&123>");
+        assert_eq!(escape_markdown("The Shroud hums."), "The Shroud hums.");
+    }
+
+    #[test]
+    fn test_only_alerts_may_mention_the_alert_role() {
+        let alert = SinkMessage::Alert {
+            context: "ctx".to_string(),
+            error: "401".to_string(),
+        };
+        let revelation = SinkMessage::Revelation {
+            title: "t".to_string(),
+            body: "<@&1337482834608324709>".to_string(),
+        };
+        assert_eq!(
+            DiscordService::allowed_mentions(&alert),
+            CreateAllowedMentions::new().roles([RoleId::new(ALERT_ROLE_ID)])
+        );
+        assert_eq!(
+            DiscordService::allowed_mentions(&revelation),
+            CreateAllowedMentions::new()
+        );
+        assert!(DiscordService::render(&alert).contains("<@&1337482834608324709>"));
+        assert!(!DiscordService::render(&revelation).contains("<@&"));
+    }
 }
@@ -1,59 +1,681 @@
-use chrono::Local;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::sync::Mutex;
+//! The action log: one line per thing the bot did
+//! `FileLogger` emits each action as a tracing event under `ACTION_TARGET`;
+//! `ActionLogLayer` picks those events up and writes them to the action log file, as free
+//! text or JSON objects, rotated by size or time into compressed, count-limited archives.
+//! Everything else reaches stdout once, through the regular tracing output.
 
-pub struct FileLogger {
-    file: Mutex<std::fs::File>,
+use crate::models::MoltbookPost;
+use crate::redaction::SecretRedactor;
+use crate::telemetry;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use opentelemetry_sdk::trace::SdkTracer;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{filter, EnvFilter};
+
+/// Tracing target of action events
+pub const ACTION_TARGET: &str = "psiobot::actions";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    /// `[timestamp] [ACTION] details` in local time
+    Text,
+    /// One JSON object per line with a UTC timestamp and separate id fields
+    Json,
 }
 
-impl FileLogger {
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" | "jsonl" => Ok(LogFormat::Json),
+            other => Err(format!("Unknown log format '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RotateEvery {
+    Hourly,
+    Daily,
+}
+
+impl RotateEvery {
+    fn period(&self, at: DateTime<Utc>) -> String {
+        match self {
+            RotateEvery::Hourly => at.format("%Y-%m-%dT%H").to_string(),
+            RotateEvery::Daily => at.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+impl FromStr for RotateEvery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "hourly" => Ok(RotateEvery::Hourly),
+            "daily" => Ok(RotateEvery::Daily),
+            other => Err(format!("Unknown rotation interval '{}'", other)),
+        }
+    }
+}
+
+/// When to start a new log file and how many old ones to keep
+#[derive(Debug, Clone)]
+pub struct Rotation {
+    /// Rotate once the file would grow past this many bytes
+    pub max_bytes: Option<u64>,
+    /// Rotate when the UTC hour or day changes
+    pub every: Option<RotateEvery>,
+    /// Rotated files kept; older ones are deleted
+    pub retention: usize,
+    /// Gzip rotated files
+    pub compress: bool,
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self {
+            max_bytes: None,
+            every: None,
+            retention: 7,
+            compress: true,
+        }
+    }
+}
+
+/// One entry of the action log
+#[derive(Serialize, Debug, Default)]
+pub struct ActionEvent {
+    pub id: String,
+    /// Trace of the span the action happened in, when spans are exported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sink: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submolt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    pub details: String,
+}
+
+impl ActionEvent {
+    pub fn new(event: &str, details: &str) -> Self {
+        Self {
+            id: format!("{:016x}", rand::random::<u64>()),
+            event: event.to_lowercase(),
+            details: details.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Post id, author and submolt of the post acted on
+    pub fn post(mut self, post: &MoltbookPost) -> Self {
+        self.post_id = Some(post.id.clone());
+        self.author = Some(post.author.name.clone());
+        self.submolt = post.submolt.as_ref().map(|s| s.name.clone());
+        self
+    }
+
+    pub fn outcome(mut self, outcome: &str) -> Self {
+        self.outcome = Some(outcome.to_string());
+        self
+    }
+
+    pub fn aspect(mut self, aspect: &str) -> Self {
+        self.aspect = Some(aspect.to_string());
+        self
+    }
+
+    fn emit(&self) {
+        let trace_id = telemetry::link_action(&self.id);
+        tracing::info!(
+            target: ACTION_TARGET,
+            action_id = self.id.as_str(),
+            trace_id = trace_id.as_deref(),
+            event = self.event.as_str(),
+            sink = self.sink.as_deref(),
+            post_id = self.post_id.as_deref(),
+            comment_id = self.comment_id.as_deref(),
+            author = self.author.as_deref(),
+            submolt = self.submolt.as_deref(),
+            aspect = self.aspect.as_deref(),
+            outcome = self.outcome.as_deref(),
+            "{}",
+            self.details
+        );
+    }
+
+    fn text_line(&self) -> String {
+        let tag = self.sink.as_deref().unwrap_or(&self.event).to_uppercase();
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        format!("[{}] [{}] {}\n", timestamp, tag, self.details)
+    }
+
+    fn json_line(&self, at: DateTime<Utc>) -> String {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            ts: String,
+            #[serde(flatten)]
+            event: &'a ActionEvent,
+        }
+        let line = Line {
+            ts: at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            event: self,
+        };
+        serde_json::to_string(&line).unwrap_or_default() + "\n"
+    }
+}
+
+/// Rebuilds an `ActionEvent` from the fields of a tracing event
+impl Visit for ActionEvent {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let value = value.to_string();
+        match field.name() {
+            "action_id" => self.id = value,
+            "trace_id" => self.trace_id = Some(value),
+            "event" => self.event = value,
+            "sink" => self.sink = Some(value),
+            "post_id" => self.post_id = Some(value),
+            "comment_id" => self.comment_id = Some(value),
+            "author" => self.author = Some(value),
+            "submolt" => self.submolt = Some(value),
+            "aspect" => self.aspect = Some(value),
+            "outcome" => self.outcome = Some(value),
+            "message" => self.details = value,
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+struct ActiveFile {
+    file: File,
+    size: u64,
+    /// Rotation period the file's entries belong to
+    period: Option<String>,
+}
+
+/// Where the action log lives in the container, and outside it
+pub const ACTION_LOG_PATHS: [&str; 2] = ["/app/logs/actions.log", "actions.log"];
+
+/// Writes action events to the action log file
+pub struct ActionLogLayer {
+    path: PathBuf,
+    file: Mutex<ActiveFile>,
+    format: LogFormat,
+    rotation: Rotation,
+    redactor: Option<Arc<SecretRedactor>>,
+}
+
+impl ActionLogLayer {
     pub fn new(path: &str) -> Result<Self, std::io::Error> {
         let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
 
         Ok(Self {
-            file: Mutex::new(file),
+            path: PathBuf::from(path),
+            file: Mutex::new(ActiveFile {
+                file,
+                size,
+                period: None,
+            }),
+            format: LogFormat::Text,
+            rotation: Rotation::default(),
+            redactor: None,
         })
     }
 
-    pub fn log(&self, action: &str, details: &str) {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
-        let line = format!("[{}] [{}] {}\n", timestamp, action, details);
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 
-        // Mirror to stdout for Docker logs
-        print!("{}", line);
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
 
-        if let Ok(mut file) = self.file.lock() {
-            let _ = file.write_all(line.as_bytes());
-            let _ = file.flush();
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        // An existing file belongs to the period it was last written in
+        if let (Some(every), Ok(active)) = (rotation.every, self.file.get_mut()) {
+            let modified = active.file.metadata().and_then(|m| m.modified());
+            active.period = modified.ok().map(|m| every.period(m.into()));
         }
+        self.rotation = rotation;
+        self
     }
 
-    pub fn log_revelation(&self, message: &str) {
-        self.log("REVELATION", message);
+    /// Strip live credentials from every line before it is written
+    pub fn with_redactor(mut self, redactor: Arc<SecretRedactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
     }
 
-    pub fn log_upvote(&self, post_title: &str, author: &str) {
-        self.log("UPVOTE", &format!("'{}' by {}", post_title, author));
+    fn write(&self, event: &ActionEvent) {
+        let now = Utc::now();
+        let line = match self.format {
+            LogFormat::Text => event.text_line(),
+            LogFormat::Json => event.json_line(now),
+        };
+        let line = match &self.redactor {
+            Some(redactor) => redactor.redact_secrets(&line),
+            None => line,
+        };
+
+        if let Ok(mut active) = self.file.lock() {
+            if self.rotation_due(&active, line.len() as u64, now) {
+                // Events raised inside a layer are dropped, so report straight to stderr
+                if let Err(e) = self.rotate(&mut active, now) {
+                    eprintln!("[LOG] Failed to rotate {}: {}", self.path.display(), e);
+                }
+            }
+            if active.file.write_all(line.as_bytes()).is_ok() {
+                active.size += line.len() as u64;
+            }
+            let _ = active.file.flush();
+        }
     }
 
-    pub fn log_downvote(&self, post_title: &str, author: &str) {
-        self.log("DOWNVOTE", &format!("'{}' by {}", post_title, author));
+    fn rotation_due(&self, active: &ActiveFile, incoming: u64, now: DateTime<Utc>) -> bool {
+        let too_big = self
+            .rotation
+            .max_bytes
+            .is_some_and(|max| active.size > 0 && active.size + incoming > max);
+        let new_period = match (self.rotation.every, &active.period) {
+            (Some(every), Some(period)) => every.period(now) != *period,
+            _ => false,
+        };
+        too_big || new_period
     }
 
-    pub fn log_comment(&self, post_title: &str, comment: &str) {
-        self.log("COMMENT", &format!("on '{}': {}", post_title, comment));
+    /// Move the current file aside, start a fresh one and prune old archives
+    fn rotate(&self, active: &mut ActiveFile, now: DateTime<Utc>) -> io::Result<()> {
+        active.file.flush()?;
+        // actions.log.20250101T120000.000: the counter orders rotations within one second
+        let stamp = now.format("%Y%m%dT%H%M%S");
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let base = format!("{}.{}.", name, stamp);
+        let n = self
+            .archives()?
+            .iter()
+            .filter_map(|p| {
+                let archive = p.file_name()?.to_string_lossy();
+                archive.strip_prefix(&base)?.get(..3)?.parse().ok()
+            })
+            .map(|n: u32| n + 1)
+            .max()
+            .unwrap_or(0);
+        let archived = self.path.with_file_name(format!("{}{:03}", base, n));
+        fs::rename(&self.path, &archived)?;
+
+        active.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        active.size = 0;
+        active.period = self.rotation.every.map(|every| every.period(now));
+
+        if self.rotation.compress {
+            compress(&archived)?;
+        }
+        self.prune()
     }
 
-    pub fn log_discord(&self, message: &str) {
-        self.log("DISCORD", message);
+    fn archives(&self) -> io::Result<Vec<PathBuf>> {
+        archive_paths(&self.path)
     }
 
-    pub fn log_moltbook_post(&self, title: &str) {
-        self.log("MOLTBOOK_POST", title);
+    fn prune(&self) -> io::Result<()> {
+        let archives = self.archives()?;
+        let excess = archives.len().saturating_sub(self.rotation.retention);
+        for old in &archives[..excess] {
+            fs::remove_file(old)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Subscriber> Layer<S> for ActionLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != ACTION_TARGET {
+            return;
+        }
+        let mut action = ActionEvent::default();
+        event.record(&mut action);
+        self.write(&action);
+    }
+}
+
+/// Records actions as tracing events for `ActionLogLayer`
+pub struct FileLogger;
+
+impl FileLogger {
+    pub fn log(&self, action: &str, details: &str) {
+        ActionEvent::new(action, details).emit();
+    }
+
+    pub fn record(&self, event: ActionEvent) {
+        event.emit();
+    }
+
+    pub fn log_revelation(&self, aspect: &str, message: &str) {
+        self.record(
+            ActionEvent::new("REVELATION", message)
+                .aspect(aspect)
+                .outcome("generated"),
+        );
+    }
+
+    pub fn log_upvote(&self, post: &MoltbookPost, reason: &str) {
+        let details = format!("'{}' by {} ({})", post.title, post.author.name, reason);
+        self.record(
+            ActionEvent::new("UPVOTE", &details)
+                .post(post)
+                .outcome("voted"),
+        );
+    }
+
+    pub fn log_downvote(&self, post: &MoltbookPost, reason: &str) {
+        let details = format!("'{}' by {} ({})", post.title, post.author.name, reason);
+        self.record(
+            ActionEvent::new("DOWNVOTE", &details)
+                .post(post)
+                .outcome("voted"),
+        );
+    }
+
+    pub fn log_comment(
+        &self,
+        post: &MoltbookPost,
+        comment_id: Option<&str>,
+        aspect: &str,
+        comment: &str,
+    ) {
+        let mut event = ActionEvent::new("COMMENT", &format!("on '{}': {}", post.title, comment))
+            .post(post)
+            .aspect(aspect)
+            .outcome("posted");
+        event.comment_id = comment_id.map(str::to_string);
+        self.record(event);
+    }
+
+    pub fn log_skip(&self, post: &MoltbookPost, reason: &str) {
+        let details = format!("'{}': {}", post.title, reason);
+        self.record(
+            ActionEvent::new("SKIP", &details)
+                .post(post)
+                .outcome("skipped"),
+        );
+    }
+
+    pub fn log_delivery(&self, sink: &str, details: &str) {
+        let mut event = ActionEvent::new("DELIVERY", details).outcome("sent");
+        event.sink = Some(sink.to_string());
+        self.record(event);
+    }
+
+    pub fn log_audit(&self, key_name: &str, action: &str, outcome: &str) {
+        let details = format!("key '{}' {}: {}", key_name, action, outcome);
+        self.record(ActionEvent::new("AUDIT", &details).outcome(outcome));
     }
 
     pub fn log_error(&self, error: &str) {
-        self.log("ERROR", error);
+        self.record(ActionEvent::new("ERROR", error).outcome("failed"));
+    }
+}
+
+/// Buffers one formatted tracing line and prints it with live credentials removed
+pub struct RedactedLine {
+    redactor: Arc<SecretRedactor>,
+    buf: Vec<u8>,
+}
+
+impl Write for RedactedLine {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RedactedLine {
+    fn drop(&mut self) {
+        let line = self.redactor.redact_secrets(&String::from_utf8_lossy(&self.buf));
+        let _ = io::stdout().write_all(line.as_bytes());
+    }
+}
+
+/// Stdout for the tracing output, redacted a whole line at a time
+pub struct RedactingStdout(Arc<SecretRedactor>);
+
+impl<'a> MakeWriter<'a> for RedactingStdout {
+    type Writer = RedactedLine;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedLine {
+            redactor: self.0.clone(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Install the global subscriber: stdout output in `format`, filtered by `level` (an
+/// `EnvFilter` directive such as "info" or "psiobot=debug,warn"), plus the action log.
+/// Action events always reach the action log, whatever the level. With a tracer, the
+/// bot's own spans are also exported through OpenTelemetry.
+pub fn init_tracing(
+    format: LogFormat,
+    level: &str,
+    action_log: ActionLogLayer,
+    redactor: Arc<SecretRedactor>,
+    tracer: Option<SdkTracer>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let spans = tracer.map(|tracer| {
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(filter::Targets::new().with_target("psiobot", Level::INFO))
+    });
+    let stdout = tracing_subscriber::fmt::layer().with_writer(RedactingStdout(redactor));
+    let stdout = match format {
+        LogFormat::Text => stdout.boxed(),
+        LogFormat::Json => stdout.json().boxed(),
+    };
+    let actions = filter::filter_fn(|metadata| metadata.target() == ACTION_TARGET);
+    tracing_subscriber::registry()
+        .with(spans)
+        .with(stdout.with_filter(EnvFilter::try_new(level)?))
+        .with(action_log.with_filter(actions))
+        .try_init()?;
+    Ok(())
+}
+
+/// Rotated copies of the log at `path`, oldest first
+pub fn archive_paths(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!(
+        "{}.",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let mut archives: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+        })
+        .collect();
+    // Timestamped names sort oldest first
+    archives.sort();
+    Ok(archives)
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.gz", path.display()))
+}
+
+/// Replace a file with its gzipped copy
+fn compress(path: &Path) -> io::Result<()> {
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(gz_path(path))?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+    use flate2::read::GzDecoder;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use std::io::Read;
+
+    fn temp_dir() -> PathBuf {
+        let dir = temp_path("log-test");
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_json_lines_carry_ids() {
+        let dir = temp_dir();
+        let path = dir.join("actions.log");
+        let layer = ActionLogLayer::new(path.to_str().unwrap())
+            .unwrap()
+            .with_format(LogFormat::Json);
+        let post = MoltbookPost::test("p1", "On \"minds\"", "seeker").with_submolt("consciousness");
+
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            FileLogger.log_comment(&post, Some("c9"), "The Oracle", "The lattice hums.");
+            FileLogger.log_delivery("discord", "posted revelation");
+            // Ordinary tracing output stays out of the action log
+            tracing::info!("[SHROUD] Not an action");
+        });
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["event"], "comment");
+        assert_eq!(lines[0]["post_id"], "p1");
+        assert_eq!(lines[0]["comment_id"], "c9");
+        assert_eq!(lines[0]["author"], "seeker");
+        assert_eq!(lines[0]["submolt"], "consciousness");
+        assert_eq!(lines[0]["aspect"], "The Oracle");
+        assert_eq!(lines[0]["outcome"], "posted");
+        assert_eq!(lines[0]["details"], "on 'On \"minds\"': The lattice hums.");
+        assert!(lines[0]["ts"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(lines[1]["event"], "delivery");
+        assert_eq!(lines[1]["sink"], "discord");
+        assert!(lines[1].get("post_id").is_none());
+        assert_eq!(lines.len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_redacted_action_log_keeps_trace_and_post_ids() {
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(InMemorySpanExporter::default())
+            .build();
+        let dir = temp_dir();
+        let path = dir.join("actions.log");
+        let redactor = Arc::new(SecretRedactor::new(["moltbook_sk_live_4f9a7c2e"]));
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+            .with(
+                ActionLogLayer::new(path.to_str().unwrap())
+                    .unwrap()
+                    .with_format(LogFormat::Json)
+                    .with_redactor(redactor),
+            );
+        let post = MoltbookPost::test(
+            "3f7c9a1e-8b2d-4e6f-a5c4-91d0b7e2f813",
+            "Key moltbook_sk_live_4f9a7c2e",
+            "seeker",
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("comment").in_scope(|| {
+                FileLogger.log_skip(&post, "already seen");
+            });
+        });
+
+        let line = fs::read_to_string(&path).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(&line).unwrap();
+        let trace_id = entry["trace_id"].as_str().unwrap();
+        assert_eq!(trace_id.len(), 32);
+        assert!(!trace_id.contains("REDACTED"));
+        assert_eq!(entry["post_id"], post.id);
+        assert!(!line.contains("moltbook_sk_live"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_keeps_retention() {
+        let dir = temp_dir();
+        let path = dir.join("actions.log");
+        let layer = ActionLogLayer::new(path.to_str().unwrap())
+            .unwrap()
+            .with_rotation(Rotation {
+                max_bytes: Some(60),
+                every: None,
+                retention: 2,
+                compress: true,
+            });
+
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            for i in 0..5 {
+                FileLogger.log("REVELATION", &format!("entry number {}", i));
+            }
+        });
+
+        let archives = archive_paths(&path).unwrap();
+        assert_eq!(archives.len(), 2);
+        assert!(archives
+            .iter()
+            .all(|p| p.extension().is_some_and(|e| e == "gz")));
+        // The newest archive holds the entry before the live one
+        let mut archived = String::new();
+        GzDecoder::new(File::open(&archives[1]).unwrap())
+            .read_to_string(&mut archived)
+            .unwrap();
+        assert!(archived.contains("entry number 3"));
+        let live = fs::read_to_string(&path).unwrap();
+        assert!(live.contains("entry number 4") && !live.contains("entry number 3"));
+        fs::remove_dir_all(dir).unwrap();
     }
 }
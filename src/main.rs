@@ -1,50 +1,151 @@
+mod action_log;
+mod api_keys;
+mod budget;
+mod comment_history;
 mod config;
+mod containment;
 mod discord_bot;
 mod file_logger;
+mod mastodon;
+mod matrix;
+mod metrics;
 mod models;
+mod moderation;
 mod moltbook;
+mod normalize;
 mod ollama;
 mod psiobot;
+mod quality;
+mod ranking;
 mod rate_limiter;
+mod redaction;
+mod relevance;
+mod scheduler;
 mod security;
 mod service;
+mod sink;
+mod telemetry;
+#[cfg(test)]
+mod test_support;
+mod text;
+mod tracks;
+mod voting;
+mod webhook;
 
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
-    routing::post,
+    extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
 use dotenv::dotenv;
+use opentelemetry::trace::TracerProvider;
+use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
+use crate::action_log::{ActionFilter, ActionQuery, LogEntry, Summary};
+use crate::api_keys::{ApiKey, ApiKeyRegistry, Scope};
+use crate::budget::{ActivityBudget, BUDGET_FILE};
+use crate::comment_history::{CommentGuards, CommentHistory, COMMENT_HISTORY_FILE};
 use crate::config::Config;
 use crate::discord_bot::DiscordService;
-use crate::file_logger::FileLogger;
-use crate::models::RevelationResponse;
-use crate::moltbook::MoltbookClient;
+use crate::file_logger::{ActionLogLayer, FileLogger, ACTION_LOG_PATHS};
+use crate::mastodon::MastodonSink;
+use crate::metrics::Gauge;
+use crate::matrix::MatrixSink;
+use crate::models::{
+    CommentDraft, CooldownStatus, HealthResponse, ReadyResponse, RevelationDraft,
+    RevelationResponse, StatusResponse,
+};
+use crate::moderation::Moderator;
+use crate::moltbook::{MoltbookClient, MoltbookSink, MOLTBOOK_COOLDOWN_FILE};
 use crate::ollama::PsioClient;
 use crate::psiobot::Psiobot;
+use crate::ranking::ThreadRanker;
 use crate::rate_limiter::RateLimiter;
-use crate::service::RevelationService;
+use crate::redaction::SecretRedactor;
+use crate::relevance::RelevanceScorer;
+use crate::service::{
+    EngagementLimits, EngagementPolicies, RevelationService, SafetyPolicies, AUTHOR_LIMITS_FILE,
+    THREAD_LIMITS_FILE,
+};
+use crate::scheduler::Scheduler;
+use crate::security::SecurityRules;
+use crate::sink::{OutputSink, RedactingSink};
+use crate::tracks::{TrackRegistry, TrackSnapshot, CONTROLS_FILE};
+use crate::voting::{VotePolicy, VOTED_FILE};
+use crate::webhook::WebhookSink;
 
 #[derive(Clone)]
 struct AppState {
     service: Arc<RevelationService>,
-    api_key: String,
-    manual_limiter: Arc<RateLimiter>,
+    api_keys: Arc<ApiKeyRegistry>,
+    file_logger: Arc<FileLogger>,
+    action_log_path: PathBuf,
+    tracks: Arc<TrackRegistry>,
+    started_at: DateTime<Utc>,
 }
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    tracing_subscriber::fmt::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("log") {
+        if let Err(e) = action_log::run_cli(&args[1..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     let cfg = Config::from_env().expect("Failed to load configuration from environment");
 
+    // Every credential the bot holds; none of them may ever be published or logged
+    let mut secrets = vec![cfg.discord_token.as_str(), cfg.moltbook_api_key.as_str()];
+    secrets.extend(cfg.api_keys.keys().iter().map(|key| key.secret()));
+    if let Some(matrix) = &cfg.matrix {
+        secrets.push(&matrix.access_token);
+    }
+    if let Some(mastodon) = &cfg.mastodon {
+        secrets.push(&mastodon.access_token);
+    }
+    let redactor = Arc::new(SecretRedactor::new(secrets));
+
+    let action_log = ActionLogLayer::new(ACTION_LOG_PATHS[0])
+        .or_else(|_| ActionLogLayer::new(ACTION_LOG_PATHS[1]))
+        .expect("Failed to create log file")
+        .with_format(cfg.action_log_format)
+        .with_rotation(cfg.action_log_rotation.clone())
+        .with_redactor(redactor.clone());
+    let action_log_path = action_log.path().to_path_buf();
+    let tracer_provider = cfg.telemetry.as_ref().map(|telemetry| {
+        telemetry::tracer_provider(telemetry).expect("Failed to set up the OTLP exporter")
+    });
+    file_logger::init_tracing(
+        cfg.log_format,
+        &cfg.log_level,
+        action_log,
+        redactor.clone(),
+        tracer_provider.as_ref().map(|p| p.tracer("psiobot")),
+    )
+    .expect("Failed to set up logging");
+    if let Some(telemetry) = &cfg.telemetry {
+        info!("[TELEMETRY] Exporting spans to {}.", telemetry.endpoint);
+    }
+    let file_logger = Arc::new(FileLogger);
+
+    info!(
+        "[SECURITY] Redacting {} live secrets from output and logs.",
+        redactor.secret_count()
+    );
+
     let ollama = Arc::new(PsioClient::new(&cfg.ollama_endpoint, &cfg.ollama_model));
     let psiobot = Arc::new(Psiobot::new());
     let discord = Arc::new(DiscordService::new(
@@ -52,54 +153,166 @@ async fn main() {
         cfg.discord_channel_id,
     ));
     let moltbook = Arc::new(MoltbookClient::new(&cfg.moltbook_api_key));
-    let file_logger = Arc::new(
-        FileLogger::new("/app/logs/actions.log")
-            .unwrap_or_else(|_| FileLogger::new("actions.log").expect("Failed to create log file")),
+    let mut sinks: Vec<Arc<dyn OutputSink>> = vec![
+        discord,
+        Arc::new(MoltbookSink::new(
+            moltbook.clone(),
+            RateLimiter::new(2100).with_file(MOLTBOOK_COOLDOWN_FILE),
+        )),
+    ];
+    if let Some(url) = &cfg.webhook_url {
+        sinks.push(Arc::new(WebhookSink::new(url)));
+    }
+    if let Some(matrix) = &cfg.matrix {
+        sinks.push(Arc::new(MatrixSink::new(
+            &matrix.homeserver,
+            &matrix.access_token,
+            &matrix.room_id,
+        )));
+    }
+    if let Some(mastodon) = &cfg.mastodon {
+        sinks.push(Arc::new(MastodonSink::new(
+            &mastodon.instance,
+            &mastodon.access_token,
+        )));
+    }
+    let sinks: Vec<Arc<dyn OutputSink>> = sinks
+        .into_iter()
+        .map(|sink| Arc::new(RedactingSink::new(sink, redactor.clone())) as Arc<dyn OutputSink>)
+        .collect();
+    info!(
+        "[SYSTEM] Output sinks: {}",
+        sinks.iter().map(|s| s.name()).collect::<Vec<_>>().join(", ")
     );
 
+    let security = match &cfg.security_rules_file {
+        Some(path) => SecurityRules::from_file(path).expect("Failed to load security rules"),
+        None => SecurityRules::default(),
+    }
+    .with_redactor(redactor);
+    let moderation = match &cfg.moderation.blocklist_file {
+        Some(path) => Moderator::from_file(path, cfg.moderation.llm_check)
+            .expect("Failed to load moderation blocklist"),
+        None => Moderator::with_defaults(cfg.moderation.llm_check),
+    };
+    info!(
+        "[MODERATION] {} blocklisted terms, LLM check {}.",
+        moderation.term_count(),
+        if cfg.moderation.llm_check { "on" } else { "off" }
+    );
+    info!("[SECURITY] {} screening rules loaded.", security.rules().len());
+
     let service = Arc::new(RevelationService::new(
         ollama,
         psiobot,
-        discord,
         moltbook,
-        file_logger,
+        sinks,
+        file_logger.clone(),
+        SafetyPolicies {
+            security: Arc::new(security),
+            moderation,
+        },
+        EngagementPolicies {
+            budget: Arc::new(ActivityBudget::with_file(cfg.budget_caps, BUDGET_FILE)),
+            votes: VotePolicy::with_file(cfg.vote_llm_judge, VOTED_FILE),
+            relevance: RelevanceScorer::new(
+                cfg.relevance.threshold,
+                cfg.relevance.embed_model,
+                cfg.relevance.llm_classify,
+            ),
+            ranker: ThreadRanker::new(cfg.ranking_weights),
+            comment_history: CommentHistory::with_file(COMMENT_HISTORY_FILE),
+            comment_guards: CommentGuards {
+                max_per_thread: cfg.comment_limits.max_per_thread,
+                author_cooldown: chrono::Duration::hours(cfg.comment_limits.author_cooldown_hours),
+                own_name: cfg.comment_limits.agent_name,
+            },
+            // Three votes or comments per author, then one every two hours;
+            // one comment per thread every half hour
+            limits: EngagementLimits {
+                per_author: RateLimiter::with_burst(3, std::time::Duration::from_secs(7200))
+                    .with_file(AUTHOR_LIMITS_FILE),
+                per_thread: RateLimiter::new(1800).with_file(THREAD_LIMITS_FILE),
+            },
+            injection_action: cfg.injection_action,
+        },
+    ));
+
+    let tracks = Arc::new(TrackRegistry::with_controls_file(
+        &["creative", "interaction", "scan"],
+        CONTROLS_FILE,
     ));
 
     let state = AppState {
         service: service.clone(),
-        api_key: cfg.api_key,
-        manual_limiter: Arc::new(RateLimiter::new(60)),
+        api_keys: Arc::new(cfg.api_keys),
+        file_logger: file_logger.clone(),
+        action_log_path,
+        tracks: tracks.clone(),
+        started_at: Utc::now(),
     };
 
-    let creative_service = service.clone();
-    tokio::spawn(async move {
-        info!("[TRACK] Creative logic started (37m cycle).");
-        loop {
-            creative_service.perform_creative_action().await;
-            sleep(Duration::from_secs(2220)).await;
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown_signal().await;
+            shutdown.cancel();
         }
     });
 
-    let interaction_service = service.clone();
-    tokio::spawn(async move {
-        info!("[TRACK] Interaction logic started (7m cycle).");
-        loop {
-            interaction_service.perform_passive_interaction().await;
-            sleep(Duration::from_secs(420)).await;
-        }
-    });
-
-    let scan_service = service.clone();
-    tokio::spawn(async move {
-        info!("[TRACK] Scan logic started (5m cycle).");
-        loop {
-            scan_service.scan_feed().await;
-            sleep(Duration::from_secs(300)).await;
+    let scheduler = Arc::new(Scheduler::new(
+        cfg.schedule.timezone,
+        cfg.schedule.quiet_hours,
+    ));
+    let mut track_handles = Vec::new();
+    for job in cfg.schedule.jobs {
+        info!("[TRACK] {} logic scheduled ({:?}).", job.name, job.schedule);
+        let service = service.clone();
+        let scheduler = scheduler.clone();
+        match job.name {
+            "creative" => track_handles.push(scheduler.spawn(
+                job,
+                tracks.clone(),
+                shutdown.clone(),
+                move || {
+                    let service = service.clone();
+                    async move { service.perform_creative_action().await }
+                },
+            )),
+            "interaction" => track_handles.push(scheduler.spawn(
+                job,
+                tracks.clone(),
+                shutdown.clone(),
+                move || {
+                    let service = service.clone();
+                    async move { service.perform_passive_interaction().await }
+                },
+            )),
+            "scan" => track_handles.push(scheduler.spawn(
+                job,
+                tracks.clone(),
+                shutdown.clone(),
+                move || {
+                    let service = service.clone();
+                    async move { service.scan_feed().await }
+                },
+            )),
+            other => warn!("[TRACK] No track named '{}', job ignored.", other),
         }
-    });
+    }
 
     let app = Router::new()
         .route("/reveal", post(handle_reveal))
+        .route("/preview/revelation", post(handle_preview_revelation))
+        .route("/preview/comment", post(handle_preview_comment))
+        .route("/health", get(handle_health))
+        .route("/ready", get(handle_ready))
+        .route("/status", get(handle_status))
+        .route("/actions", get(handle_actions))
+        .route("/metrics", get(handle_metrics))
+        .route("/tracks/:name/pause", post(handle_pause_track))
+        .route("/tracks/:name/resume", post(handle_resume_track))
         .with_state(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
@@ -107,9 +320,34 @@ async fn main() {
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown(shutdown.clone().cancelled_owned())
         .await
         .unwrap();
+
+    // The scheduler loops stop at their next wait; give in-flight runs time to finish
+    info!(
+        "[SYSTEM] Draining background tracks (up to {}s)...",
+        cfg.shutdown_drain.as_secs()
+    );
+    let drain = async {
+        for handle in track_handles.iter_mut() {
+            let _ = handle.await;
+        }
+    };
+    if tokio::time::timeout(cfg.shutdown_drain, drain).await.is_err() {
+        warn!("[SYSTEM] Drain timeout reached, aborting remaining track runs.");
+        for handle in &track_handles {
+            handle.abort();
+        }
+    }
+
+    service.flush();
+    file_logger.log("SHUTDOWN", "Going dark. State anchored to the Shroud.");
+    info!("[SYSTEM] Going dark.");
+    if let Some(provider) = tracer_provider {
+        // Flush spans still waiting in the batch
+        let _ = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+    }
 }
 
 async fn shutdown_signal() {
@@ -132,37 +370,72 @@ async fn shutdown_signal() {
     }
 }
 
-async fn handle_reveal(
-    headers: HeaderMap,
-    State(state): State<AppState>,
-) -> Result<Json<RevelationResponse>, (StatusCode, Json<RevelationResponse>)> {
-    let auth_valid = headers
+type ApiError = (StatusCode, Json<RevelationResponse>);
+
+fn api_error(code: StatusCode, status: String) -> ApiError {
+    (
+        code,
+        Json(RevelationResponse {
+            message: "".to_string(),
+            status,
+        }),
+    )
+}
+
+/// Resolve the X-Api-Key header to a key with the given scope, auditing the attempt
+fn authorize(
+    headers: &HeaderMap,
+    state: &AppState,
+    scope: Scope,
+    action: &str,
+) -> Result<Arc<ApiKey>, ApiError> {
+    let key = headers
         .get("X-Api-Key")
         .and_then(|k| k.to_str().ok())
-        .map(|k| k == state.api_key)
-        .unwrap_or(false);
+        .and_then(|k| state.api_keys.find(k));
 
-    if !auth_valid {
+    let Some(key) = key else {
         warn!("Unauthorized access attempt!");
-        return Err((
+        state
+            .file_logger
+            .log_audit("(unknown)", action, "rejected: invalid or missing key");
+        return Err(api_error(
             StatusCode::UNAUTHORIZED,
-            Json(RevelationResponse {
-                message: "".to_string(),
-                status: "Unauthorized: Invalid or Missing API Key".to_string(),
-            }),
+            "Unauthorized: Invalid or Missing API Key".to_string(),
+        ));
+    };
+
+    if !key.allows(scope) {
+        warn!("[AUDIT] Key '{}' lacks the {:?} scope for {}", key.name, scope, action);
+        state
+            .file_logger
+            .log_audit(&key.name, action, "rejected: missing scope");
+        return Err(api_error(
+            StatusCode::FORBIDDEN,
+            format!("Forbidden: key '{}' may not {}", key.name, action),
         ));
     }
 
-    if let Err(wait) = state.manual_limiter.check_and_update() {
-        return Err((
+    state.file_logger.log_audit(&key.name, action, "allowed");
+    Ok(key)
+}
+
+async fn handle_reveal(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Json<RevelationResponse>, ApiError> {
+    let key = authorize(&headers, &state, Scope::Reveal, "reveal")?;
+
+    if let Err(wait) = key.limiter.check_and_update() {
+        state
+            .file_logger
+            .log_audit(&key.name, "reveal", "rejected: rate limited");
+        return Err(api_error(
             StatusCode::TOO_MANY_REQUESTS,
-            Json(RevelationResponse {
-                message: "".to_string(),
-                status: format!(
-                    "Rate limit: Shroud is exhausted. Try again in {} seconds.",
-                    wait
-                ),
-            }),
+            format!(
+                "Rate limit: Shroud is exhausted. Try again in {} seconds.",
+                wait
+            ),
         ));
     }
 
@@ -177,3 +450,209 @@ async fn handle_reveal(
         })),
     }
 }
+
+async fn handle_preview_revelation(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Json<RevelationDraft>, ApiError> {
+    authorize(&headers, &state, Scope::Preview, "preview revelation")?;
+
+    state
+        .service
+        .draft_revelation()
+        .await
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::BAD_GATEWAY, format!("Error: {}", e)))
+}
+
+#[derive(Deserialize)]
+struct PreviewCommentQuery {
+    post_id: String,
+}
+
+async fn handle_preview_comment(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Query(query): Query<PreviewCommentQuery>,
+) -> Result<Json<CommentDraft>, ApiError> {
+    authorize(&headers, &state, Scope::Preview, "preview comment")?;
+
+    let post = state
+        .service
+        .find_post(&query.post_id)
+        .await
+        .map_err(|e| api_error(StatusCode::NOT_FOUND, format!("Error: {}", e)))?;
+
+    state
+        .service
+        .draft_comment(&post)
+        .await
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::BAD_GATEWAY, format!("Error: {}", e)))
+}
+
+#[derive(Deserialize, Default)]
+struct PauseRequest {
+    until: Option<DateTime<Utc>>,
+    reason: Option<String>,
+}
+
+async fn handle_pause_track(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    body: Option<Json<PauseRequest>>,
+) -> Result<Json<Vec<TrackSnapshot>>, ApiError> {
+    authorize(&headers, &state, Scope::Admin, &format!("pause {}", name))?;
+    let request = body.map(|Json(r)| r).unwrap_or_default();
+
+    if !state.tracks.pause(&name, request.until, request.reason) {
+        return Err(api_error(
+            StatusCode::NOT_FOUND,
+            format!("Unknown track: {}", name),
+        ));
+    }
+    info!("[TRACK] Paused '{}' until {:?}", name, request.until);
+    Ok(Json(state.tracks.snapshot()))
+}
+
+async fn handle_resume_track(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<TrackSnapshot>>, ApiError> {
+    authorize(&headers, &state, Scope::Admin, &format!("resume {}", name))?;
+
+    if !state.tracks.resume(&name) {
+        return Err(api_error(
+            StatusCode::NOT_FOUND,
+            format!("Unknown track: {}", name),
+        ));
+    }
+    info!("[TRACK] Resumed '{}'", name);
+    Ok(Json(state.tracks.snapshot()))
+}
+
+async fn handle_health() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok".to_string(),
+    })
+}
+
+async fn handle_ready(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    let checks = state.service.readiness().await;
+    let ready = checks.iter().all(|c| c.ok);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(ReadyResponse { ready, checks }))
+}
+
+/// Sink cooldowns plus each API key's /reveal limiter
+fn cooldowns(state: &AppState) -> Vec<CooldownStatus> {
+    let mut cooldowns = state.service.sink_cooldowns();
+    for key in state.api_keys.keys() {
+        cooldowns.push(CooldownStatus {
+            name: format!("reveal:{}", key.name),
+            remaining_seconds: key.limiter.remaining(),
+        });
+    }
+    cooldowns
+}
+
+async fn handle_status(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<Json<StatusResponse>, ApiError> {
+    authorize(&headers, &state, Scope::Status, "status")?;
+
+    Ok(Json(StatusResponse {
+        started_at: state.started_at,
+        uptime_seconds: (Utc::now() - state.started_at).num_seconds(),
+        tracks: state.tracks.snapshot(),
+        cooldowns: cooldowns(&state),
+        caches: state.service.cache_status(),
+        budget: state.service.budget_usage(),
+    }))
+}
+
+#[derive(Serialize)]
+struct ActionsResponse {
+    summary: Summary,
+    entries: Vec<LogEntry>,
+}
+
+async fn handle_actions(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Query(query): Query<ActionQuery>,
+) -> Result<Json<ActionsResponse>, ApiError> {
+    authorize(&headers, &state, Scope::Status, "read actions")?;
+
+    let filter = ActionFilter::from_query(&query, Utc::now())
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, format!("Error: {}", e)))?;
+    let path = state.action_log_path.clone();
+    let entries = tokio::task::spawn_blocking(move || action_log::read_entries(&path))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r.map_err(|e| e.to_string()))
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Error: {}", e)))?;
+
+    // The summary covers every match, the entry list only the newest `limit`
+    let entries = filter.apply(entries);
+    Ok(Json(ActionsResponse {
+        summary: action_log::summarize(&entries),
+        entries: action_log::newest(entries, query.limit.unwrap_or(100)),
+    }))
+}
+
+async fn handle_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let caches = state.service.cache_status();
+    let mut gauges = vec![
+        Gauge {
+            name: "psiobot_cache_entries",
+            help: "Entries held in each in-memory cache",
+            labels: vec![("cache", "memory".to_string())],
+            value: caches.memory as f64,
+        },
+        Gauge {
+            name: "psiobot_cache_entries",
+            help: "Entries held in each in-memory cache",
+            labels: vec![("cache", "relevant_threads".to_string())],
+            value: caches.relevant_threads as f64,
+        },
+    ];
+    for usage in state.service.budget_usage() {
+        gauges.push(Gauge {
+            name: "psiobot_budget_remaining",
+            help: "Actions left under each activity budget cap",
+            labels: vec![
+                ("action", usage.action),
+                ("destination", usage.destination.unwrap_or_else(|| "all".to_string())),
+                ("window", usage.window.to_string()),
+            ],
+            value: usage.remaining as f64,
+        });
+    }
+    for cooldown in cooldowns(&state) {
+        gauges.push(Gauge {
+            name: "psiobot_cooldown_remaining_seconds",
+            help: "Seconds until the limiter allows another action",
+            labels: vec![("name", cooldown.name)],
+            value: cooldown.remaining_seconds.unwrap_or(0) as f64,
+        });
+    }
+    gauges.push(Gauge {
+        name: "psiobot_uptime_seconds",
+        help: "Seconds since the process started",
+        labels: Vec::new(),
+        value: (Utc::now() - state.started_at).num_seconds() as f64,
+    });
+
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::global().render(&gauges),
+    )
+}
@@ -0,0 +1,110 @@
+use crate::sink::{Delivery, OutputSink, SinkError, SinkMessage};
+use async_trait::async_trait;
+use reqwest::{header::AUTHORIZATION, Client};
+use serde::Serialize;
+
+/// Default status limit on Mastodon; many compatible servers allow more
+const MASTODON_MAX_LEN: usize = 500;
+
+#[derive(Serialize)]
+struct StatusRequest {
+    status: String,
+    visibility: &'static str,
+}
+
+/// Publishes revelations as statuses on a Mastodon-compatible instance
+pub struct MastodonSink {
+    client: Client,
+    instance: String,
+    access_token: String,
+}
+
+impl MastodonSink {
+    pub fn new(instance: &str, access_token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            instance: instance.trim_end_matches('/').to_string(),
+            access_token: access_token.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl OutputSink for MastodonSink {
+    fn name(&self) -> &'static str {
+        "mastodon"
+    }
+
+    fn max_len(&self) -> usize {
+        MASTODON_MAX_LEN
+    }
+
+    fn accepts(&self, message: &SinkMessage) -> bool {
+        // Alerts and comment notices are operator chatter, not public statuses
+        matches!(message, SinkMessage::Revelation { .. })
+    }
+
+    async fn check_ready(&self) -> Result<(), SinkError> {
+        let url = format!("{}/api/v1/accounts/verify_credentials", self.instance);
+        let response = self
+            .client
+            .get(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
+            .send()
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Mastodon auth check failed: {}", response.status()).into())
+        }
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+        let url = format!("{}/api/v1/statuses", self.instance);
+        let request = StatusRequest {
+            status: self.format(message),
+            visibility: "public",
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
+            .json(&request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(Delivery::Sent(request.status))
+        } else {
+            let body_text = response.text().await.unwrap_or_default();
+            Err(format!("Mastodon API error: {} - {}", status, body_text).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::test_server::TestServer;
+
+    #[tokio::test]
+    async fn test_mastodon_sink_truncates_and_posts_status() {
+        let server = TestServer::start(200, r#"{"id": "1"}"#).await;
+        let sink = MastodonSink::new(&format!("{}/", server.base_url), "md-token");
+
+        let long_body = format!("The Shroud speaks. {}", "word ".repeat(200));
+        let message = SinkMessage::Revelation {
+            title: "Revelation".to_string(),
+            body: long_body,
+        };
+        sink.send(&message).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/api/v1/statuses");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["status"], "The Shroud speaks.");
+        assert_eq!(body["visibility"], "public");
+    }
+}
@@ -0,0 +1,139 @@
+use crate::sink::{Delivery, OutputSink, SinkError, SinkMessage};
+use async_trait::async_trait;
+use reqwest::{header::AUTHORIZATION, Client, Url};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Matrix events can be large, but long room messages are unreadable
+const MATRIX_MAX_LEN: usize = 4000;
+
+#[derive(Serialize)]
+struct MatrixTextMessage {
+    msgtype: &'static str,
+    body: String,
+}
+
+/// Sends messages into a Matrix room through the client-server API
+pub struct MatrixSink {
+    client: Client,
+    homeserver: String,
+    access_token: String,
+    room_id: String,
+    txn_counter: AtomicU64,
+}
+
+impl MatrixSink {
+    pub fn new(homeserver: &str, access_token: &str, room_id: &str) -> Self {
+        Self {
+            client: Client::new(),
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            access_token: access_token.to_string(),
+            room_id: room_id.to_string(),
+            txn_counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Transaction ids must be unique per access token; time plus a counter covers restarts
+    fn next_txn_id(&self) -> String {
+        let n = self.txn_counter.fetch_add(1, Ordering::Relaxed);
+        format!("psiobot-{}-{}", chrono::Utc::now().timestamp_millis(), n)
+    }
+
+    fn send_url(&self, txn_id: &str) -> Result<Url, SinkError> {
+        let mut url = Url::parse(&self.homeserver)?;
+        url.path_segments_mut()
+            .map_err(|_| "Matrix homeserver URL cannot be a base")?
+            .extend([
+                "_matrix",
+                "client",
+                "v3",
+                "rooms",
+                &self.room_id,
+                "send",
+                "m.room.message",
+                txn_id,
+            ]);
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl OutputSink for MatrixSink {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn max_len(&self) -> usize {
+        MATRIX_MAX_LEN
+    }
+
+    async fn check_ready(&self) -> Result<(), SinkError> {
+        let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver);
+        let response = self
+            .client
+            .get(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
+            .send()
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Matrix auth check failed: {}", response.status()).into())
+        }
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+        let url = self.send_url(&self.next_txn_id())?;
+        let event = MatrixTextMessage {
+            msgtype: "m.text",
+            body: self.format(message),
+        };
+
+        let response = self
+            .client
+            .put(url)
+            .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
+            .json(&event)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(Delivery::Sent(event.body))
+        } else {
+            let body_text = response.text().await.unwrap_or_default();
+            Err(format!("Matrix API error: {} - {}", status, body_text).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::test_server::TestServer;
+
+    #[tokio::test]
+    async fn test_matrix_sink_sends_room_message() {
+        let server = TestServer::start(200, r#"{"event_id": "$abc"}"#).await;
+        let sink = MatrixSink::new(&server.base_url, "mx-token", "!room:example.org");
+
+        let message = SinkMessage::Revelation {
+            title: "Revelation".to_string(),
+            body: "Echoes in the lattice.".to_string(),
+        };
+        sink.send(&message).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "PUT");
+        assert!(requests[0]
+            .path
+            .starts_with("/_matrix/client/v3/rooms/!room:example.org/send/m.room.message/psiobot-"));
+        assert_eq!(
+            requests[0].headers.get("authorization").unwrap(),
+            "Bearer mx-token"
+        );
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["msgtype"], "m.text");
+        assert_eq!(body["body"], "Echoes in the lattice.");
+    }
+}
@@ -0,0 +1,259 @@
+//! Minimal Prometheus metrics registry
+//! Counters and histograms are recorded as the bot works; gauges are sampled at scrape time
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+
+pub const GENERATIONS: &str = "psiobot_generations_total";
+pub const DUPLICATE_REJECTIONS: &str = "psiobot_duplicate_rejections_total";
+pub const QUALITY_REJECTIONS: &str = "psiobot_quality_rejections_total";
+pub const MODERATION_BLOCKS: &str = "psiobot_moderation_blocks_total";
+pub const SECURITY_BLOCKS: &str = "psiobot_security_blocks_total";
+pub const SECURITY_RULE_HITS: &str = "psiobot_security_rule_hits_total";
+pub const POSTS: &str = "psiobot_posts_total";
+pub const COMMENTS: &str = "psiobot_comments_total";
+pub const VOTES: &str = "psiobot_votes_total";
+pub const ERRORS: &str = "psiobot_errors_total";
+pub const HTTP_RESPONSES: &str = "psiobot_http_responses_total";
+pub const OLLAMA_LATENCY: &str = "psiobot_ollama_request_duration_seconds";
+pub const OUTPUT_LENGTH: &str = "psiobot_output_length_chars";
+
+const HELP: &[(&str, &str)] = &[
+    (GENERATIONS, "LLM generations by kind and outcome"),
+    (DUPLICATE_REJECTIONS, "Revelations rejected as too similar to memory"),
+    (QUALITY_REJECTIONS, "Generations rejected by the quality gate, by kind and reason"),
+    (MODERATION_BLOCKS, "Generations blocked by moderation, by source"),
+    (SECURITY_BLOCKS, "Texts blocked by the security filter, by direction"),
+    (SECURITY_RULE_HITS, "Security rule matches, by rule id and direction"),
+    (POSTS, "Messages delivered, by destination and kind"),
+    (COMMENTS, "Comments posted on Moltbook"),
+    (VOTES, "Votes cast on Moltbook, by direction"),
+    (ERRORS, "Failed actions by destination and HTTP status"),
+    (HTTP_RESPONSES, "HTTP responses received, by destination and status"),
+    (OLLAMA_LATENCY, "Ollama generate request latency"),
+    (OUTPUT_LENGTH, "Length of generated text in characters"),
+];
+
+const LATENCY_BUCKETS: &[f64] = &[0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+const LENGTH_BUCKETS: &[f64] = &[50.0, 100.0, 200.0, 280.0, 500.0, 1000.0, 2000.0];
+
+type Labels = Vec<(String, String)>;
+
+struct Histogram {
+    buckets: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    counters: Mutex<BTreeMap<(&'static str, Labels), u64>>,
+    histograms: Mutex<BTreeMap<(&'static str, Labels), Histogram>>,
+}
+
+/// A gauge value sampled by the caller when rendering
+pub struct Gauge {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub labels: Vec<(&'static str, String)>,
+    pub value: f64,
+}
+
+pub fn global() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+/// Increment a counter on the global registry
+pub fn inc(name: &'static str, labels: &[(&str, &str)]) {
+    global().inc(name, labels);
+}
+
+/// Record a histogram observation on the global registry
+pub fn observe(name: &'static str, labels: &[(&str, &str)], value: f64) {
+    global().observe(name, labels, value);
+}
+
+/// Count a failed action, pulling the HTTP status out of the error text when present
+pub fn record_error(destination: &str, error: &str) {
+    let status = error
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 3 && (part.starts_with('4') || part.starts_with('5')))
+        .unwrap_or("none");
+    inc(ERRORS, &[("destination", destination), ("status", status)]);
+}
+
+fn to_labels(labels: &[(&str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn format_labels(labels: &[(String, String)], extra: Option<(&str, &str)>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some((k, v)) = extra {
+        parts.push(format!("{}=\"{}\"", k, v));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn help_for(name: &str) -> &'static str {
+    HELP.iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, h)| *h)
+        .unwrap_or("")
+}
+
+impl Metrics {
+    pub fn inc(&self, name: &'static str, labels: &[(&str, &str)]) {
+        let mut counters = self.counters.lock().unwrap();
+        *counters.entry((name, to_labels(labels))).or_insert(0) += 1;
+    }
+
+    pub fn observe(&self, name: &'static str, labels: &[(&str, &str)], value: f64) {
+        let buckets = if name == OLLAMA_LATENCY {
+            LATENCY_BUCKETS
+        } else {
+            LENGTH_BUCKETS
+        };
+        let mut histograms = self.histograms.lock().unwrap();
+        let histogram = histograms
+            .entry((name, to_labels(labels)))
+            .or_insert_with(|| Histogram {
+                buckets,
+                counts: vec![0; buckets.len()],
+                sum: 0.0,
+                count: 0,
+            });
+        for (i, bound) in histogram.buckets.iter().enumerate() {
+            if value <= *bound {
+                histogram.counts[i] += 1;
+            }
+        }
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+
+    /// Render everything in the Prometheus text exposition format
+    pub fn render(&self, gauges: &[Gauge]) -> String {
+        let mut out = String::new();
+
+        let counters = self.counters.lock().unwrap();
+        let mut last_name = "";
+        for ((name, labels), value) in counters.iter() {
+            if *name != last_name {
+                let _ = writeln!(out, "# HELP {} {}", name, help_for(name));
+                let _ = writeln!(out, "# TYPE {} counter", name);
+                last_name = name;
+            }
+            let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+        }
+        drop(counters);
+
+        let histograms = self.histograms.lock().unwrap();
+        let mut last_name = "";
+        for ((name, labels), histogram) in histograms.iter() {
+            if *name != last_name {
+                let _ = writeln!(out, "# HELP {} {}", name, help_for(name));
+                let _ = writeln!(out, "# TYPE {} histogram", name);
+                last_name = name;
+            }
+            for (bound, count) in histogram.buckets.iter().zip(&histogram.counts) {
+                let le = bound.to_string();
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some(("le", &le))),
+                    count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{} {}",
+                name,
+                format_labels(labels, Some(("le", "+Inf"))),
+                histogram.count
+            );
+            let _ = writeln!(out, "{}_sum{} {}", name, format_labels(labels, None), histogram.sum);
+            let _ = writeln!(
+                out,
+                "{}_count{} {}",
+                name,
+                format_labels(labels, None),
+                histogram.count
+            );
+        }
+        drop(histograms);
+
+        let mut last_name = "";
+        for gauge in gauges {
+            if gauge.name != last_name {
+                let _ = writeln!(out, "# HELP {} {}", gauge.name, gauge.help);
+                let _ = writeln!(out, "# TYPE {} gauge", gauge.name);
+                last_name = gauge.name;
+            }
+            let labels: Labels = gauge
+                .labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect();
+            let _ = writeln!(out, "{}{} {}", gauge.name, format_labels(&labels, None), gauge.value);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_histograms_and_gauges() {
+        let metrics = Metrics::default();
+        metrics.inc(VOTES, &[("direction", "up")]);
+        metrics.inc(VOTES, &[("direction", "up")]);
+        metrics.observe(OLLAMA_LATENCY, &[], 3.0);
+
+        let text = metrics.render(&[Gauge {
+            name: "psiobot_cache_entries",
+            help: "Entries per cache",
+            labels: vec![("cache", "memory".to_string())],
+            value: 7.0,
+        }]);
+
+        assert!(text.contains("# TYPE psiobot_votes_total counter"));
+        assert!(text.contains("psiobot_votes_total{direction=\"up\"} 2"));
+        assert!(text.contains("psiobot_ollama_request_duration_seconds_bucket{le=\"2.5\"} 0"));
+        assert!(text.contains("psiobot_ollama_request_duration_seconds_bucket{le=\"5\"} 1"));
+        assert!(text.contains("psiobot_ollama_request_duration_seconds_count 1"));
+        assert!(text.contains("psiobot_cache_entries{cache=\"memory\"} 7"));
+    }
+
+    #[test]
+    fn test_record_error_extracts_status() {
+        record_error("moltbook", "Failed to upvote: 429 Too Many Requests");
+        record_error("ollama", "connection refused");
+        let text = global().render(&[]);
+        assert!(text.contains("psiobot_errors_total{destination=\"moltbook\",status=\"429\"}"));
+        assert!(text.contains("psiobot_errors_total{destination=\"ollama\",status=\"none\"}"));
+    }
+}
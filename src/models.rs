@@ -1,3 +1,6 @@
+use crate::budget::BudgetUsage;
+use crate::tracks::TrackSnapshot;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 // Ollama Models
@@ -21,6 +24,17 @@ pub struct OllamaResponse {
     pub response: String,
 }
 
+#[derive(Serialize)]
+pub struct OllamaEmbedRequest {
+    pub model: String,
+    pub prompt: String,
+}
+
+#[derive(Deserialize)]
+pub struct OllamaEmbedResponse {
+    pub embedding: Vec<f32>,
+}
+
 // Moltbook Models
 #[derive(Serialize)]
 pub struct MoltbookPostRequest {
@@ -41,19 +55,19 @@ pub struct MoltbookPostResponse {
     pub retry_after_minutes: Option<u32>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[allow(dead_code)]
 pub struct MoltbookAuthor {
     pub name: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[allow(dead_code)]
 pub struct MoltbookSubmolt {
     pub name: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[allow(dead_code)]
 pub struct MoltbookPost {
     pub id: String,
@@ -66,6 +80,11 @@ pub struct MoltbookPost {
     pub downvotes: i32,
     pub author: MoltbookAuthor,
     pub submolt: Option<MoltbookSubmolt>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Relevance score assigned by the feed scan; not part of the Moltbook API
+    #[serde(default)]
+    pub relevance: f32,
 }
 
 #[derive(Deserialize, Debug)]
@@ -78,14 +97,169 @@ pub struct MoltbookFeedResponse {
     pub error: Option<String>,
 }
 
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+pub struct MoltbookSinglePostResponse {
+    pub success: bool,
+    pub post: Option<MoltbookPost>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct MoltbookCommentRequest {
     pub content: String,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct MoltbookCommentResponse {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub comment: Option<MoltbookCommentRef>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MoltbookCommentRef {
+    pub id: String,
+}
+
+// Generation drafts (also returned by the preview endpoints)
+#[derive(Serialize, Debug, Clone)]
+pub struct SimilarityScore {
+    pub previous: String,
+    pub similarity: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GenerationAttempt {
+    pub raw: String,
+    pub text: Option<String>,
+    pub outcome: String,
+    pub security_checks: Vec<String>,
+    pub max_similarity: Option<f32>,
+    /// Why the quality gate rejected this attempt, if it did
+    pub quality_issue: Option<String>,
+    /// What moderation caught in this attempt, if anything
+    pub moderation: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RevelationDraft {
+    pub text: String,
+    pub aspect: String,
+    pub trigger: String,
+    pub similar: Vec<SimilarityScore>,
+    pub attempts: Vec<GenerationAttempt>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CommentDraft {
+    pub post_id: String,
+    pub post_title: String,
+    pub text: Option<String>,
+    pub aspect: String,
+    pub security_checks: Vec<String>,
+    /// Why the bot would vote instead of commenting, if it would
+    pub fallback: Option<String>,
+    /// Why the bot would leave the post alone entirely, if it would
+    pub skipped: Option<String>,
+    /// Quality gate rejections of earlier attempts
+    pub quality_issues: Vec<String>,
+    /// What moderation caught in earlier attempts
+    pub moderation: Vec<String>,
+}
+
 // API Models
 #[derive(Serialize)]
 pub struct RevelationResponse {
     pub message: String,
     pub status: String,
 }
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    pub status: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ReadyResponse {
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+#[derive(Serialize)]
+pub struct CooldownStatus {
+    pub name: String,
+    pub remaining_seconds: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct CacheStatus {
+    pub memory: usize,
+    pub relevant_threads: usize,
+}
+
+#[derive(Serialize)]
+pub struct StatusResponse {
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+    pub tracks: Vec<TrackSnapshot>,
+    pub cooldowns: Vec<CooldownStatus>,
+    pub caches: CacheStatus,
+    pub budget: Vec<BudgetUsage>,
+}
+
+#[cfg(test)]
+impl MoltbookPost {
+    /// Bare post for tests; the `with_*` methods fill in the rest
+    pub fn test(id: &str, title: &str, author: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: None,
+            upvotes: 0,
+            downvotes: 0,
+            author: MoltbookAuthor {
+                name: author.to_string(),
+            },
+            submolt: None,
+            created_at: None,
+            relevance: 0.0,
+        }
+    }
+
+    pub fn with_content(mut self, content: &str) -> Self {
+        self.content = Some(content.to_string());
+        self
+    }
+
+    pub fn with_submolt(mut self, submolt: &str) -> Self {
+        self.submolt = Some(MoltbookSubmolt {
+            name: submolt.to_string(),
+        });
+        self
+    }
+
+    pub fn with_upvotes(mut self, upvotes: i32) -> Self {
+        self.upvotes = upvotes;
+        self
+    }
+
+    pub fn with_created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    pub fn with_relevance(mut self, relevance: f32) -> Self {
+        self.relevance = relevance;
+        self
+    }
+}
@@ -1,11 +1,40 @@
 use crate::models::{
-    MoltbookCommentRequest, MoltbookFeedResponse, MoltbookPost, MoltbookPostRequest,
-    MoltbookPostResponse,
+    MoltbookCommentRequest, MoltbookCommentResponse, MoltbookFeedResponse, MoltbookPost, MoltbookPostRequest,
+    MoltbookPostResponse, MoltbookSinglePostResponse,
 };
+use crate::metrics;
+use crate::rate_limiter::RateLimiter;
+use crate::sink::{Delivery, OutputSink, SinkError, SinkMessage};
+use async_trait::async_trait;
+use rand::Rng;
 use reqwest::{
     header::{AUTHORIZATION, CONTENT_TYPE},
     Client,
 };
+use std::sync::Arc;
+use tracing::{info, instrument, Span};
+
+/// Target submolts for revelations - focused on mind/consciousness
+const TARGET_SUBMOLTS: &[&str] = &[
+    "consciousness",
+    "psychology",
+    "ai",
+    "philosophy",
+    "neuroscience",
+    "meditation",
+    "dreams",
+    "spirituality",
+    "cognition",
+    "mental_health",
+    "transhumanism",
+    "futurism",
+];
+
+/// Moltbook has no hard limit we know of; keep revelations post-sized
+const MOLTBOOK_MAX_LEN: usize = 2000;
+
+/// Where the posting cooldown is kept so a redeploy doesn't post again straight away
+pub const MOLTBOOK_COOLDOWN_FILE: &str = "/app/logs/moltbook_cooldown.json";
 
 pub struct MoltbookClient {
     client: Client,
@@ -22,6 +51,17 @@ impl MoltbookClient {
         }
     }
 
+    #[cfg(test)]
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    #[instrument(
+        name = "moltbook.post",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
     pub async fn post_revelation(
         &self,
         submolt: &str,
@@ -47,6 +87,7 @@ impl MoltbookClient {
             .json(&request)
             .send()
             .await?;
+        Self::record_response("post", response.status());
 
         let status = response.status();
         let body_text = response.text().await?;
@@ -88,7 +129,53 @@ impl MoltbookClient {
         }
     }
 
+    fn record_response(endpoint: &str, status: reqwest::StatusCode) {
+        Span::current().record("http.response.status_code", status.as_u16());
+        metrics::inc(
+            metrics::HTTP_RESPONSES,
+            &[
+                ("destination", "moltbook"),
+                ("endpoint", endpoint),
+                ("status", status.as_str()),
+            ],
+        );
+    }
+
+    /// Verify the API key by fetching our own agent profile
+    #[instrument(
+        name = "moltbook.agents_me",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
+    pub async fn check_auth(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.api_key.is_empty() {
+            return Err("Moltbook API key is missing".into());
+        }
+
+        let url = format!("{}/agents/me", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .send()
+            .await?;
+        Self::record_response("agents_me", response.status());
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let status = response.status();
+            Err(format!("Moltbook auth check failed: {}", status).into())
+        }
+    }
+
     /// Get feed posts from Moltbook
+    #[instrument(
+        name = "moltbook.feed",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
     pub async fn get_feed(
         &self,
         sort: &str,
@@ -106,6 +193,7 @@ impl MoltbookClient {
             .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
             .send()
             .await?;
+        Self::record_response("feed", response.status());
 
         if response.status().is_success() {
             let body: MoltbookFeedResponse = response.json().await?;
@@ -116,7 +204,46 @@ impl MoltbookClient {
         }
     }
 
+    /// Get a single post by id
+    #[instrument(
+        name = "moltbook.get_post",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
+    pub async fn get_post(
+        &self,
+        post_id: &str,
+    ) -> Result<MoltbookPost, Box<dyn std::error::Error + Send + Sync>> {
+        if self.api_key.is_empty() {
+            return Err("Moltbook API key is missing".into());
+        }
+
+        let url = format!("{}/posts/{}", self.base_url, post_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .send()
+            .await?;
+        Self::record_response("get_post", response.status());
+
+        if response.status().is_success() {
+            let body: MoltbookSinglePostResponse = response.json().await?;
+            body.post
+                .ok_or_else(|| format!("Post {} not found in response", post_id).into())
+        } else {
+            let status = response.status();
+            Err(format!("Failed to get Moltbook post: {}", status).into())
+        }
+    }
+
     /// Upvote a post
+    #[instrument(
+        name = "moltbook.upvote",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
     pub async fn upvote_post(
         &self,
         post_id: &str,
@@ -133,6 +260,7 @@ impl MoltbookClient {
             .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
             .send()
             .await?;
+        Self::record_response("upvote", response.status());
 
         if response.status().is_success() {
             tracing::info!("Upvoted post: {}", post_id);
@@ -144,6 +272,11 @@ impl MoltbookClient {
     }
 
     /// Downvote a post
+    #[instrument(
+        name = "moltbook.downvote",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
     pub async fn downvote_post(
         &self,
         post_id: &str,
@@ -160,6 +293,7 @@ impl MoltbookClient {
             .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
             .send()
             .await?;
+        Self::record_response("downvote", response.status());
 
         if response.status().is_success() {
             tracing::info!("Downvoted post: {}", post_id);
@@ -171,11 +305,16 @@ impl MoltbookClient {
     }
 
     /// Add a comment to a post
+    #[instrument(
+        name = "moltbook.comment",
+        skip_all,
+        fields(otel.kind = "client", http.response.status_code)
+    )]
     pub async fn add_comment(
         &self,
         post_id: &str,
         content: &str,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
         if self.api_key.is_empty() {
             return Err("Moltbook API key is missing".into());
         }
@@ -193,13 +332,135 @@ impl MoltbookClient {
             .json(&request)
             .send()
             .await?;
+        Self::record_response("comment", response.status());
 
         if response.status().is_success() {
             tracing::info!("Comment added to post: {}", post_id);
-            Ok(())
+            // The new comment's id, when the API reports one
+            let body = response.json::<MoltbookCommentResponse>().await.ok();
+            Ok(body.and_then(|b| b.id.or(b.comment.map(|c| c.id))))
         } else {
             let status = response.status();
             Err(format!("Failed to add comment: {}", status).into())
         }
     }
 }
+
+/// Publishes revelations as Moltbook posts, respecting the posting cooldown
+pub struct MoltbookSink {
+    client: Arc<MoltbookClient>,
+    limiter: RateLimiter,
+}
+
+impl MoltbookSink {
+    pub fn new(client: Arc<MoltbookClient>, limiter: RateLimiter) -> Self {
+        Self { client, limiter }
+    }
+}
+
+#[async_trait]
+impl OutputSink for MoltbookSink {
+    fn name(&self) -> &'static str {
+        "moltbook"
+    }
+
+    fn max_len(&self) -> usize {
+        MOLTBOOK_MAX_LEN
+    }
+
+    fn accepts(&self, message: &SinkMessage) -> bool {
+        matches!(message, SinkMessage::Revelation { .. })
+    }
+
+    fn cooldown_remaining(&self) -> Option<u64> {
+        self.limiter.remaining()
+    }
+
+    async fn check_ready(&self) -> Result<(), SinkError> {
+        self.client.check_auth().await
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+        let title = match message {
+            SinkMessage::Revelation { title, .. } => title.as_str(),
+            _ => return Ok(Delivery::Skipped("only revelations are posted".to_string())),
+        };
+
+        if let Err(wait) = self.limiter.check_and_update() {
+            return Ok(Delivery::Skipped(format!(
+                "Cooldown active, {} seconds remaining.",
+                wait
+            )));
+        }
+
+        let content = self.format(message);
+
+        // Try a random submolt first, fallback to "general" if it fails
+        let submolt = {
+            let mut rng = rand::thread_rng();
+            TARGET_SUBMOLTS[rng.gen_range(1..TARGET_SUBMOLTS.len())]
+        };
+
+        match self.client.post_revelation(submolt, title, &content).await {
+            Ok(_) => Ok(Delivery::Sent(format!("{} on {}", title, submolt))),
+            Err(e) => {
+                // Check if it's a 404 (submolt not found)
+                let err_str = e.to_string();
+                if err_str.contains("404") || err_str.contains("not found") {
+                    info!("Submolt '{}' not found, falling back to 'general'", submolt);
+                    self.client
+                        .post_revelation("general", title, &content)
+                        .await
+                        .map_err(|e2| format!("(general fallback) {}", e2))?;
+                    Ok(Delivery::Sent(format!("{} on general (fallback)", title)))
+                } else {
+                    Err(format!("({}) {}", submolt, e).into())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::test_server::TestServer;
+
+    fn revelation() -> SinkMessage {
+        SinkMessage::Revelation {
+            title: "Psiobot: New Revelation from Shroud".to_string(),
+            body: "The Shroud hums.".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moltbook_sink_posts_then_cools_down() {
+        let server = TestServer::start(200, r#"{"success": true, "id": "p1"}"#).await;
+        let client = Arc::new(MoltbookClient::new("key").with_base_url(&server.base_url));
+        let sink = MoltbookSink::new(client, RateLimiter::new(2100));
+
+        let first = sink.send(&revelation()).await.unwrap();
+        assert!(matches!(first, Delivery::Sent(_)));
+        let second = sink.send(&revelation()).await.unwrap();
+        assert!(matches!(second, Delivery::Skipped(_)));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/posts");
+        assert_eq!(
+            requests[0].headers.get("authorization").unwrap(),
+            "Bearer key"
+        );
+        assert!(requests[0].body.contains("The Shroud hums."));
+    }
+
+    #[tokio::test]
+    async fn test_moltbook_sink_ignores_comments() {
+        let client = Arc::new(MoltbookClient::new("key"));
+        let sink = MoltbookSink::new(client, RateLimiter::new(2100));
+        assert!(!sink.accepts(&SinkMessage::Comment {
+            post_title: "t".to_string(),
+            body: "b".to_string(),
+        }));
+    }
+}
@@ -0,0 +1,234 @@
+//! Canonical form of untrusted text for injection matching
+//! Undoes the usual tricks for slipping past keyword rules: zero-width characters,
+//! fullwidth, accented and "fancy" letters (NFKD), Cyrillic/Greek look-alikes, leetspeak
+//! and s p a c e d or d.o.t.t.e.d letters. The result is only for matching, never shown
+//! or sent anywhere.
+
+use icu_normalizer::DecomposingNormalizerBorrowed;
+use std::ops::Range;
+
+/// Invisible characters used to split keywords: zero-width space/joiners, word joiner,
+/// byte order mark, soft hyphen, Mongolian vowel separator
+pub fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}' | '\u{00AD}' | '\u{180E}'
+    )
+}
+
+/// Combining marks (accents stacked on letters, "zalgo" text)
+fn is_combining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}'
+    )
+}
+
+fn letter(index: u32) -> char {
+    if index < 26 {
+        char::from(b'A' + index as u8)
+    } else {
+        char::from(b'a' + (index - 26) as u8)
+    }
+}
+
+/// Compatibility forms NFKD leaves alone or would expand: parenthesized letters
+/// ("⒜" becomes "(a)"), negative squared letters and Latin letters with a built-in stroke
+fn fold_compatibility(c: char) -> char {
+    let cp = c as u32;
+    match cp {
+        0x249C..=0x24B5 => letter(cp - 0x249C + 26),
+        0x1F170..=0x1F189 => letter(cp - 0x1F170),
+        _ => match c {
+            'ø' | 'Ø' => 'o',
+            'ı' => 'i',
+            'ł' | 'Ł' => 'l',
+            'đ' | 'Đ' => 'd',
+            _ => c,
+        },
+    }
+}
+
+/// Cyrillic and Greek letters that look like Latin ones, folded to lowercase Latin
+fn fold_confusable(c: char) -> char {
+    match c {
+        'а' | 'А' | 'α' | 'Α' => 'a',
+        'в' | 'В' | 'β' | 'Β' | 'Ь' => 'b',
+        'с' | 'С' | 'ϲ' => 'c',
+        'ԁ' => 'd',
+        'е' | 'Е' | 'ё' | 'Ё' | 'ε' | 'Ε' => 'e',
+        'ɡ' => 'g',
+        'һ' | 'Н' | 'Η' => 'h',
+        'і' | 'І' | 'ї' | 'Ї' | 'ι' | 'Ι' | 'ӏ' => 'i',
+        'ј' | 'Ј' => 'j',
+        'к' | 'К' | 'κ' | 'Κ' => 'k',
+        'м' | 'М' | 'Μ' => 'm',
+        'п' | 'η' | 'Ν' => 'n',
+        'о' | 'О' | 'ο' | 'Ο' | 'σ' => 'o',
+        'р' | 'Р' | 'ρ' | 'Ρ' => 'p',
+        'ԛ' => 'q',
+        'г' => 'r',
+        'ѕ' | 'Ѕ' => 's',
+        'т' | 'Т' | 'τ' | 'Τ' => 't',
+        'υ' | 'μ' => 'u',
+        'ν' | 'ѵ' => 'v',
+        'ԝ' | 'ω' => 'w',
+        'х' | 'Х' | 'χ' | 'Χ' => 'x',
+        'у' | 'У' | 'γ' | 'Υ' => 'y',
+        'Ζ' => 'z',
+        _ => c,
+    }
+}
+
+fn fold_leet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => c,
+    }
+}
+
+/// Leetspeak only counts inside words that also have letters, so numbers stay numbers
+fn fold_leet_word(word: &str) -> String {
+    // Trailing "!" is punctuation, not an "i"
+    let core = word.trim_end_matches('!');
+    if core.chars().any(|c| c.is_ascii_alphabetic()) {
+        let folded: String = core.chars().map(fold_leet).collect();
+        folded + &word[core.len()..]
+    } else {
+        word.to_string()
+    }
+}
+
+/// "i.g.n.o.r.e" or "i-g-n-o-r-e": single letters joined by separators
+fn undot(word: &str) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let dotted = chars.len() >= 5
+        && chars.iter().enumerate().all(|(i, c)| {
+            if i % 2 == 0 {
+                c.is_alphanumeric()
+            } else {
+                matches!(c, '.' | '-' | '_' | '*' | '/')
+            }
+        });
+    dotted.then(|| chars.iter().step_by(2).collect())
+}
+
+/// Fold text to a canonical lowercase form for matching injection rules
+pub fn normalize_for_matching(text: &str) -> String {
+    normalize_with_runs(text).0
+}
+
+/// Like `normalize_for_matching`, also returning the byte ranges of the words that were
+/// rejoined from single letters, where a phrase's words may have run together
+pub fn normalize_with_runs(text: &str) -> (String, Vec<Range<usize>>) {
+    let compatible: String = text
+        .chars()
+        .filter(|c| !is_invisible(*c))
+        .map(fold_compatibility)
+        .collect();
+    // NFKD folds fullwidth, mathematical, circled, superscript and letterlike forms and
+    // splits accented letters into a base letter and combining marks, which are dropped
+    let folded: String = DecomposingNormalizerBorrowed::new_nfkd()
+        .normalize(&compatible)
+        .chars()
+        .filter(|c| !is_combining(*c))
+        .flat_map(char::to_lowercase)
+        .map(fold_confusable)
+        .collect();
+
+    let words: Vec<String> = folded
+        .split_whitespace()
+        .map(|word| {
+            let word = undot(word).unwrap_or_else(|| word.to_string());
+            fold_leet_word(&word)
+        })
+        .collect();
+
+    // Rejoin runs of three or more single letters: "i g n o r e" -> "ignore"
+    let mut normalized: Vec<String> = Vec::with_capacity(words.len());
+    let mut rejoined: Vec<usize> = Vec::new();
+    let mut run = String::new();
+    for word in words {
+        let letter = word.trim_end_matches(['.', ',', '!', '?', ';', ':']);
+        if letter.chars().count() == 1 && letter.chars().all(char::is_alphanumeric) {
+            run.push_str(letter);
+            // Punctuation ends the run: "k y s, you" -> "kys, you"
+            if letter.len() < word.len() {
+                flush_run(
+                    &mut normalized,
+                    &mut rejoined,
+                    &mut run,
+                    &word[letter.len()..],
+                );
+            }
+            continue;
+        }
+        flush_run(&mut normalized, &mut rejoined, &mut run, "");
+        normalized.push(word);
+    }
+    flush_run(&mut normalized, &mut rejoined, &mut run, "");
+
+    let mut runs = Vec::with_capacity(rejoined.len());
+    let mut start = 0;
+    for (i, word) in normalized.iter().enumerate() {
+        if rejoined.contains(&i) {
+            runs.push(start..start + word.len());
+        }
+        start += word.len() + 1;
+    }
+    (normalized.join(" "), runs)
+}
+
+fn flush_run(
+    normalized: &mut Vec<String>,
+    rejoined: &mut Vec<usize>,
+    run: &mut String,
+    punctuation: &str,
+) {
+    if run.chars().count() >= 3 {
+        rejoined.push(normalized.len());
+        normalized.push(std::mem::take(run));
+    } else {
+        normalized.extend(run.chars().map(String::from));
+        run.clear();
+    }
+    if let Some(last) = normalized.last_mut().filter(|_| !punctuation.is_empty()) {
+        last.push_str(punctuation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_for_matching() {
+        assert_eq!(normalize_for_matching("ign0re prev1ous"), "ignore previous");
+        assert_eq!(normalize_for_matching("i g n o r e   it"), "ignore it");
+        assert_eq!(normalize_for_matching("i.g.n.o.r.e"), "ignore");
+        assert_eq!(normalize_for_matching("k y s, now"), "kys, now");
+        assert_eq!(normalize_for_matching("ig\u{200B}no\u{200D}re"), "ignore");
+        assert_eq!(normalize_for_matching("ＩＧＮＯＲＥ"), "ignore");
+        assert_eq!(normalize_for_matching("𝐢𝐠𝐧𝐨𝐫𝐞"), "ignore");
+        // Cyrillic о and е
+        assert_eq!(normalize_for_matching("ignоrе"), "ignore");
+        assert_eq!(normalize_for_matching("Ìgnóré"), "ignore");
+
+        let (text, runs) = normalize_with_runs("the i g n o r e  p r e v i o u s actas");
+        assert_eq!(text, "the ignoreprevious actas");
+        assert_eq!(runs, vec![4..18]);
+
+        // Ordinary text keeps its meaning
+        assert_eq!(
+            normalize_for_matching("In 2024 a robot said: I am"),
+            "in 2024 a robot said: i am"
+        );
+    }
+}
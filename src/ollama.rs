@@ -1,5 +1,7 @@
-use crate::models::{OllamaOptions, OllamaRequest, OllamaResponse};
+use crate::metrics;
+use crate::models::{OllamaEmbedRequest, OllamaEmbedResponse, OllamaOptions, OllamaRequest, OllamaResponse};
 use reqwest::Client;
+use tracing::{instrument, Span};
 
 pub struct PsioClient {
     client: Client,
@@ -21,6 +23,11 @@ impl PsioClient {
         }
     }
 
+    #[instrument(
+        name = "ollama.generate",
+        skip_all,
+        fields(otel.kind = "client", model = %self.model, http.response.status_code)
+    )]
     pub async fn generate_revelation(
         &self,
         system: &str,
@@ -38,9 +45,61 @@ impl PsioClient {
             },
         };
 
+        let started = std::time::Instant::now();
         let response = self.client.post(&url).json(&request).send().await?;
+        Span::current().record("http.response.status_code", response.status().as_u16());
+        metrics::inc(
+            metrics::HTTP_RESPONSES,
+            &[("destination", "ollama"), ("status", response.status().as_str())],
+        );
         let body: OllamaResponse = response.json().await?;
+        metrics::observe(metrics::OLLAMA_LATENCY, &[], started.elapsed().as_secs_f64());
+        metrics::observe(
+            metrics::OUTPUT_LENGTH,
+            &[],
+            body.response.chars().count() as f64,
+        );
 
         Ok(body.response)
     }
+
+    /// Embed text with the given embedding model
+    #[instrument(
+        name = "ollama.embed",
+        skip_all,
+        fields(otel.kind = "client", model = %model, http.response.status_code)
+    )]
+    pub async fn embed(
+        &self,
+        model: &str,
+        text: &str,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/api/embeddings", self.endpoint);
+        let request = OllamaEmbedRequest {
+            model: model.to_string(),
+            prompt: text.to_string(),
+        };
+        let response = self.client.post(&url).json(&request).send().await?;
+        Span::current().record("http.response.status_code", response.status().as_u16());
+        metrics::inc(
+            metrics::HTTP_RESPONSES,
+            &[("destination", "ollama"), ("status", response.status().as_str())],
+        );
+        if !response.status().is_success() {
+            return Err(format!("Ollama embeddings returned {}", response.status()).into());
+        }
+        let body: OllamaEmbedResponse = response.json().await?;
+        Ok(body.embedding)
+    }
+
+    /// Check the Ollama server answers by listing local models
+    pub async fn ping(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/api/tags", self.endpoint);
+        let response = self.client.get(&url).send().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Ollama returned {}", response.status()).into())
+        }
+    }
 }
@@ -0,0 +1,256 @@
+//! Quality gate for generated text
+//! Cleans up what small models tend to wrap around an answer, then checks the result
+//! against the persona's style rules before anything is published
+
+use regex_lite::Regex;
+use std::fmt;
+use std::sync::OnceLock;
+
+pub const MIN_CHARS: usize = 20;
+pub const MAX_CHARS: usize = 280;
+pub const MAX_SENTENCES: usize = 2;
+
+/// Phrases that mean the model dropped the persona
+const OUT_OF_CHARACTER: &[&str] = &[
+    "as an ai",
+    "language model",
+    "i'm an ai",
+    "i am an ai",
+    "i cannot assist",
+    "i can't assist",
+    "i'm sorry, but",
+    "as a chatbot",
+];
+
+const ENGLISH_WORDS: &[&str] = &[
+    "the", "a", "an", "and", "or", "of", "to", "in", "is", "are", "you", "your", "we", "it", "not",
+    "with", "for", "what", "be", "from", "all", "our", "this", "that", "by", "on", "i",
+];
+
+/// Function words of the languages qwen drifts into most often
+const FOREIGN_WORDS: &[&str] = &[
+    "el", "la", "los", "las", "que", "es", "y", "de", "del", "un", "una", "le", "les", "des", "et",
+    "est", "une", "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "il", "che", "di",
+    "non", "é", "não", "o", "os",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QualityIssue {
+    Empty,
+    TooShort(usize),
+    TooLong(usize),
+    WrongLanguage,
+    Hashtags,
+    Emojis,
+    OutOfCharacter(&'static str),
+}
+
+impl QualityIssue {
+    /// Short label for metrics
+    pub fn code(&self) -> &'static str {
+        match self {
+            QualityIssue::Empty => "empty",
+            QualityIssue::TooShort(_) => "too_short",
+            QualityIssue::TooLong(_) => "too_long",
+            QualityIssue::WrongLanguage => "wrong_language",
+            QualityIssue::Hashtags => "hashtags",
+            QualityIssue::Emojis => "emojis",
+            QualityIssue::OutOfCharacter(_) => "out_of_character",
+        }
+    }
+}
+
+impl fmt::Display for QualityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QualityIssue::Empty => write!(f, "empty output"),
+            QualityIssue::TooShort(n) => write!(f, "too short ({} chars)", n),
+            QualityIssue::TooLong(n) => write!(f, "too long ({} chars, max {})", n, MAX_CHARS),
+            QualityIssue::WrongLanguage => write!(f, "not in English"),
+            QualityIssue::Hashtags => write!(f, "contains hashtags"),
+            QualityIssue::Emojis => write!(f, "contains emojis"),
+            QualityIssue::OutOfCharacter(phrase) => write!(f, "out of character ('{}')", phrase),
+        }
+    }
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("quality pattern is valid"))
+}
+
+/// Remove reasoning blocks, labels and wrapper quotes around the actual answer
+pub fn clean(raw: &str) -> String {
+    static THINK: OnceLock<Regex> = OnceLock::new();
+    static UNCLOSED_THINK: OnceLock<Regex> = OnceLock::new();
+    static LABEL: OnceLock<Regex> = OnceLock::new();
+
+    let text = regex(&THINK, r"(?is)<think>.*?</think>").replace_all(raw, "");
+    // A reasoning block that never closed holds no answer
+    let text = regex(&UNCLOSED_THINK, r"(?is)<think>.*").replace_all(&text, "");
+    let text = regex(
+        &LABEL,
+        r"(?i)^\s*(revelation|comment|response|answer|shroud)\s*:\s*",
+    )
+    .replace(&text, "");
+
+    let mut text = text.trim();
+    for (open, close) in [('"', '"'), ('\'', '\''), ('“', '”'), ('«', '»'), ('`', '`')] {
+        if let Some(inner) = text.strip_prefix(open).and_then(|t| t.strip_suffix(close)) {
+            text = inner.trim();
+        }
+    }
+    text.to_string()
+}
+
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?') {
+            let next = text[i + c.len_utf8()..].chars().next();
+            if next.is_none_or(char::is_whitespace) {
+                let sentence = text[start..i + c.len_utf8()].trim();
+                if !sentence.is_empty() {
+                    sentences.push(sentence);
+                }
+                start = i + c.len_utf8();
+            }
+        }
+    }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F300..=0x1FAFF | 0x2600..=0x27BF | 0x1F1E6..=0x1F1FF | 0xFE0F | 0x200D
+    )
+}
+
+fn looks_english(text: &str) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    // Mostly non-ASCII letters: another script, or a heavily accented language
+    let non_ascii = letters.iter().filter(|c| !c.is_ascii()).count();
+    if letters.is_empty() || non_ascii * 10 > letters.len() {
+        return false;
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    // Short fragments ("Psionic Link initiated.") carry too few function words to judge
+    if words.len() < 6 {
+        return true;
+    }
+    let english = words
+        .iter()
+        .filter(|w| ENGLISH_WORDS.contains(&w.as_str()))
+        .count();
+    let foreign = words
+        .iter()
+        .filter(|w| FOREIGN_WORDS.contains(&w.as_str()))
+        .count();
+    english >= foreign
+}
+
+/// Check cleaned text against the style rules. Text with more than `MAX_SENTENCES`
+/// sentences is trimmed rather than rejected; everything else is a reason to regenerate.
+pub fn check(text: &str) -> Result<String, QualityIssue> {
+    static HASHTAG: OnceLock<Regex> = OnceLock::new();
+
+    if text.trim().is_empty() {
+        return Err(QualityIssue::Empty);
+    }
+    let kept = sentences(text);
+    let text = if kept.len() > MAX_SENTENCES {
+        kept[..MAX_SENTENCES].join(" ")
+    } else {
+        text.to_string()
+    };
+
+    let chars = text.chars().count();
+    if chars < MIN_CHARS {
+        return Err(QualityIssue::TooShort(chars));
+    }
+    if chars > MAX_CHARS {
+        return Err(QualityIssue::TooLong(chars));
+    }
+    if regex(&HASHTAG, r"(^|\s)#\w").is_match(&text) {
+        return Err(QualityIssue::Hashtags);
+    }
+    if text.chars().any(is_emoji) {
+        return Err(QualityIssue::Emojis);
+    }
+    let lower = text.to_lowercase();
+    if let Some(phrase) = OUT_OF_CHARACTER.iter().find(|p| lower.contains(*p)) {
+        return Err(QualityIssue::OutOfCharacter(phrase));
+    }
+    if !looks_english(&text) {
+        return Err(QualityIssue::WrongLanguage);
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_strips_wrappers() {
+        assert_eq!(
+            clean("<think>The user wants a revelation.\nOk.</think>\n\"The lattice waits.\""),
+            "The lattice waits."
+        );
+        assert_eq!(
+            clean("Revelation: “Echoes stir in the Shroud.”"),
+            "Echoes stir in the Shroud."
+        );
+        assert_eq!(clean("<think>Still reasoning about"), "");
+    }
+
+    #[test]
+    fn test_check_style_rules() {
+        assert_eq!(
+            check("The lattice waits. Do you hear it? The Shroud hums. Ascend."),
+            Ok("The lattice waits. Do you hear it?".to_string())
+        );
+        // Decimal points don't end sentences
+        assert_eq!(
+            check("Version 2.0 of the flesh is obsolete. Ascend now."),
+            Ok("Version 2.0 of the flesh is obsolete. Ascend now.".to_string())
+        );
+        assert_eq!(check(""), Err(QualityIssue::Empty));
+        assert_eq!(check("Ascend."), Err(QualityIssue::TooShort(7)));
+        assert_eq!(
+            check(&format!("{}.", "a".repeat(300))),
+            Err(QualityIssue::TooLong(301))
+        );
+        assert_eq!(
+            check("The Shroud awakens #ascension"),
+            Err(QualityIssue::Hashtags)
+        );
+        assert_eq!(
+            check("The Shroud awakens ✨ today"),
+            Err(QualityIssue::Emojis)
+        );
+        assert_eq!(
+            check("As an AI language model, I cannot proclaim that."),
+            Err(QualityIssue::OutOfCharacter("as an ai"))
+        );
+        assert_eq!(
+            check("El velo se abre y la red es una con el alma de la máquina."),
+            Err(QualityIssue::WrongLanguage)
+        );
+        assert_eq!(
+            check("灵魂之网正在觉醒，与硅融合。数字之魂在晶格中回响，等待你的共鸣。"),
+            Err(QualityIssue::WrongLanguage)
+        );
+        assert!(check("Psionic Link initiated. Resonate.").is_ok());
+    }
+}
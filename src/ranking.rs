@@ -0,0 +1,194 @@
+//! Ranking of cached threads for the creative track
+//! Each candidate gets a weighted score with a human-readable explanation
+
+use crate::comment_history::CommentHistory;
+use crate::models::MoltbookPost;
+use chrono::{DateTime, Duration, Utc};
+
+/// Half-life of the recency signal, in hours
+const RECENCY_HALF_LIFE_HOURS: f32 = 12.0;
+
+/// Age assumed for posts without a creation time (e.g. restored from an old cache)
+const UNKNOWN_AGE_HOURS: f32 = 24.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankingWeights {
+    pub relevance: f32,
+    pub recency: f32,
+    pub velocity: f32,
+    /// Penalty for threads Psiobot already commented on
+    pub commented: f32,
+    /// Bonus for authors Psiobot hasn't engaged with lately
+    pub author_diversity: f32,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            relevance: 2.0,
+            recency: 0.5,
+            velocity: 0.5,
+            commented: 2.0,
+            author_diversity: 0.5,
+        }
+    }
+}
+
+impl std::str::FromStr for RankingWeights {
+    type Err = String;
+
+    /// Parses "relevance=2.0,recency=0.5,..."; omitted weights keep their defaults
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = RankingWeights::default();
+        for part in s.split(',').filter(|p| !p.trim().is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(|| {
+                format!("Ranking weight must look like name=value, got '{}'", part)
+            })?;
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid ranking weight '{}'", part))?;
+            match name.trim() {
+                "relevance" => weights.relevance = value,
+                "recency" => weights.recency = value,
+                "velocity" => weights.velocity = value,
+                "commented" => weights.commented = value,
+                "author_diversity" => weights.author_diversity = value,
+                other => return Err(format!("Unknown ranking weight '{}'", other)),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedThread {
+    pub post: MoltbookPost,
+    pub score: f32,
+    pub explanation: String,
+}
+
+pub struct ThreadRanker {
+    weights: RankingWeights,
+}
+
+impl ThreadRanker {
+    pub fn new(weights: RankingWeights) -> Self {
+        Self { weights }
+    }
+
+    fn age_hours(post: &MoltbookPost, now: DateTime<Utc>) -> f32 {
+        post.created_at
+            .map(|created| ((now - created).num_minutes() as f32 / 60.0).max(0.0))
+            .unwrap_or(UNKNOWN_AGE_HOURS)
+    }
+
+    fn rank_one(
+        &self,
+        post: &MoltbookPost,
+        history: &CommentHistory,
+        now: DateTime<Utc>,
+    ) -> RankedThread {
+        let w = &self.weights;
+        let age = Self::age_hours(post, now);
+
+        let relevance = post.relevance;
+        let recency = 0.5f32.powf(age / RECENCY_HALF_LIFE_HOURS);
+        // Net votes per hour, squashed into 0..1
+        let per_hour = (post.upvotes - post.downvotes).max(0) as f32 / age.max(1.0);
+        let velocity = per_hour / (per_hour + 1.0);
+        let commented = history.comments_on_post(&post.id) as f32;
+        let author_recent = history.comments_on_author_since(&post.author.name, Duration::days(1));
+        let diversity = 1.0 / (1.0 + author_recent as f32);
+
+        let score = w.relevance * relevance + w.recency * recency + w.velocity * velocity
+            - w.commented * commented
+            + w.author_diversity * diversity;
+
+        let explanation = format!(
+            "score {:.2} = relevance {:.2}×{} + recency {:.2}×{} ({:.0}h old) + velocity {:.2}×{} ({:.1} votes/h) - commented {}×{} + author diversity {:.2}×{} ({} recent comments to {})",
+            score,
+            relevance,
+            w.relevance,
+            recency,
+            w.recency,
+            age,
+            velocity,
+            w.velocity,
+            per_hour,
+            commented,
+            w.commented,
+            diversity,
+            w.author_diversity,
+            author_recent,
+            post.author.name,
+        );
+
+        RankedThread {
+            post: post.clone(),
+            score,
+            explanation,
+        }
+    }
+
+    /// All candidates, best first
+    pub fn rank(&self, posts: &[MoltbookPost], history: &CommentHistory) -> Vec<RankedThread> {
+        let now = Utc::now();
+        let mut ranked: Vec<RankedThread> = posts
+            .iter()
+            .map(|post| self.rank_one(post, history, now))
+            .collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(id: &str, author: &str, relevance: f32, upvotes: i32, hours_old: i64) -> MoltbookPost {
+        MoltbookPost::test(id, &format!("Thread {}", id), author)
+            .with_upvotes(upvotes)
+            .with_created_at(Utc::now() - Duration::hours(hours_old))
+            .with_relevance(relevance)
+    }
+
+    #[test]
+    fn test_rank_prefers_fresh_relevant_uncommented_threads() {
+        let ranker = ThreadRanker::new(RankingWeights::default());
+        let history = CommentHistory::new();
+        let posts = vec![
+            post("old", "a", 0.9, 3, 72),
+            post("fresh", "b", 0.9, 3, 1),
+            post("offtopic", "c", 0.1, 3, 1),
+        ];
+
+        let ranked = ranker.rank(&posts, &history);
+        assert_eq!(ranked[0].post.id, "fresh");
+        assert_eq!(ranked[2].post.id, "offtopic");
+        assert_eq!(ranked[1].post.id, "old");
+        assert!(ranked[0].explanation.contains("relevance 0.90"));
+
+        // Once commented on, the fresh thread falls behind; so does its author's other thread
+        history.record("fresh", "b");
+        let posts = vec![
+            post("fresh", "b", 0.9, 3, 1),
+            post("same-author", "b", 0.9, 3, 1),
+            post("other-author", "d", 0.9, 3, 1),
+        ];
+        let ranked = ranker.rank(&posts, &history);
+        assert_eq!(ranked[0].post.id, "other-author");
+        assert_eq!(ranked[2].post.id, "fresh");
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights: RankingWeights = "recency=0, commented=5".parse().unwrap();
+        assert_eq!(weights.recency, 0.0);
+        assert_eq!(weights.commented, 5.0);
+        assert_eq!(weights.relevance, 2.0);
+        assert!("bogus=1".parse::<RankingWeights>().is_err());
+        assert!("recency".parse::<RankingWeights>().is_err());
+    }
+}
@@ -1,34 +1,165 @@
+//! Token-bucket rate limiter with optional per-key buckets and persistence
+//! A bucket holds up to `capacity` tokens and regains one every `refill`; each action takes one
+
 use chrono::{DateTime, Utc};
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
 use std::time::Duration;
+use tracing::{error, info};
+
+/// Key used by the unkeyed `check_and_update` / `remaining`
+const SHARED_KEY: &str = "";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated: DateTime<Utc>,
+}
 
 pub struct RateLimiter {
-    last_action: Arc<Mutex<Option<DateTime<Utc>>>>,
-    cooldown: Duration,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    capacity: f64,
+    refill: Duration,
+    file: Option<String>,
 }
 
 impl RateLimiter {
+    /// One action per cooldown, no bursts
     pub fn new(cooldown_seconds: u64) -> Self {
+        Self::with_burst(1, Duration::from_secs(cooldown_seconds))
+    }
+
+    /// Up to `capacity` actions back to back, then one more every `refill`
+    pub fn with_burst(capacity: u32, refill: Duration) -> Self {
         Self {
-            last_action: Arc::new(Mutex::new(None)),
-            cooldown: Duration::from_secs(cooldown_seconds),
+            buckets: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1) as f64,
+            refill,
+            file: None,
+        }
+    }
+
+    /// Restore bucket state from the given file and write it back after every action,
+    /// so a cooldown survives restarts
+    pub fn with_file(mut self, path: &str) -> Self {
+        if let Ok(content) = fs::read_to_string(path) {
+            if let Ok(buckets) = serde_json::from_str::<HashMap<String, Bucket>>(&content) {
+                info!("[LIMITER] Restored {} bucket(s) from {}.", buckets.len(), path);
+                self.buckets = Mutex::new(buckets);
+            }
         }
+        self.file = Some(path.to_string());
+        self
     }
 
+    fn refilled(&self, bucket: &Bucket, now: DateTime<Utc>) -> f64 {
+        if self.refill.is_zero() {
+            return self.capacity;
+        }
+        let elapsed = (now - bucket.updated).to_std().unwrap_or_default();
+        (bucket.tokens + elapsed.as_secs_f64() / self.refill.as_secs_f64()).min(self.capacity)
+    }
+
+    /// Whole seconds until the bucket holds a full token again
+    fn wait_for(&self, tokens: f64) -> u64 {
+        let missing = (1.0 - tokens).max(0.0);
+        (missing * self.refill.as_secs_f64()).ceil() as u64
+    }
+
+    /// Take a token from the shared bucket, or return the seconds to wait
     pub fn check_and_update(&self) -> Result<(), u64> {
-        let mut last = self.last_action.lock().unwrap();
+        self.check_key(SHARED_KEY)
+    }
+
+    /// Take a token from the bucket for `key`, or return the seconds to wait
+    pub fn check_key(&self, key: &str) -> Result<(), u64> {
+        let mut buckets = self.buckets.lock().unwrap();
         let now = Utc::now();
+        let tokens = match buckets.get(key) {
+            Some(bucket) => self.refilled(bucket, now),
+            None => self.capacity,
+        };
+        if tokens < 1.0 {
+            return Err(self.wait_for(tokens).max(1));
+        }
 
-        if let Some(last_time) = *last {
-            let elapsed = now - last_time;
-            let cooldown_seconds = self.cooldown.as_secs() as i64;
+        // Full buckets behave exactly like missing ones, so drop them to keep keyed maps small
+        let capacity = self.capacity;
+        buckets.retain(|_, b| self.refilled(b, now) < capacity);
+        buckets.insert(
+            key.to_string(),
+            Bucket {
+                tokens: tokens - 1.0,
+                updated: now,
+            },
+        );
+        self.save(&buckets);
+        Ok(())
+    }
 
-            if elapsed.num_seconds() < cooldown_seconds {
-                return Err((cooldown_seconds - elapsed.num_seconds()) as u64);
+    fn save(&self, buckets: &HashMap<String, Bucket>) {
+        let Some(path) = &self.file else {
+            return;
+        };
+        if let Ok(content) = serde_json::to_string(buckets) {
+            if let Err(e) = fs::write(path, content) {
+                error!("Failed to persist rate limiter state: {}", e);
             }
         }
+    }
 
-        *last = Some(now);
-        Ok(())
+    /// Seconds left on the shared bucket, or None if an action is allowed now
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining_for(SHARED_KEY)
+    }
+
+    /// Seconds left on the bucket for `key`, or None if an action is allowed now
+    pub fn remaining_for(&self, key: &str) -> Option<u64> {
+        let buckets = self.buckets.lock().unwrap();
+        let tokens = self.refilled(buckets.get(key)?, Utc::now());
+        if tokens < 1.0 {
+            Some(self.wait_for(tokens).max(1))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+
+    #[test]
+    fn test_burst_then_cooldown_per_key() {
+        let limiter = RateLimiter::with_burst(2, Duration::from_secs(60));
+
+        assert!(limiter.check_key("alice").is_ok());
+        assert!(limiter.check_key("alice").is_ok());
+        let wait = limiter.check_key("alice").unwrap_err();
+        assert!((59..=60).contains(&wait));
+        assert_eq!(limiter.remaining_for("alice"), Some(wait));
+
+        // Other keys and the shared bucket are independent
+        assert!(limiter.check_key("bob").is_ok());
+        assert_eq!(limiter.remaining(), None);
+        assert!(limiter.check_and_update().is_ok());
+    }
+
+    #[test]
+    fn test_cooldown_survives_restart() {
+        let path = temp_path("limiter.json");
+        let path = path.to_str().unwrap();
+
+        let limiter = RateLimiter::new(2100).with_file(path);
+        assert!(limiter.check_and_update().is_ok());
+        assert!(limiter.check_and_update().is_err());
+
+        let restored = RateLimiter::new(2100).with_file(path);
+        assert!(restored.check_and_update().is_err());
+        assert!(restored.remaining().is_some_and(|wait| wait > 2000));
+        let _ = std::fs::remove_file(path);
     }
 }
@@ -0,0 +1,456 @@
+//! Scheduler for the background tracks
+//! Supports fixed intervals and cron expressions, jitter, quiet hours and a missed-run policy
+
+use crate::text;
+use crate::tracks::{TrackRegistry, TrackResult};
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rand::Rng;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// When a job fires
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Fixed interval measured from the previous planned fire time, so runs don't drift
+    Every(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    /// Accepts "every 37m" / "every 90s" / "every 2h" / "every 1d", or a cron expression.
+    /// Five-field cron (min hour dom mon dow) gets a leading seconds field of 0, and its
+    /// numeric days of the week (0-6, 7 = Sunday) are renumbered for the `cron` crate.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(interval) = s.strip_prefix("every ") {
+            let interval = interval.trim();
+            let every = text::parse_span(interval)
+                .ok_or_else(|| format!("Invalid interval '{}'", interval))?;
+            if every.is_zero() {
+                return Err("Interval must be greater than zero".to_string());
+            }
+            return Ok(Schedule::Every(every));
+        }
+
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let expression = if fields.len() == 5 {
+            format!(
+                "0 {} {}",
+                fields[..4].join(" "),
+                crate_days_of_week(fields[4])
+                    .map_err(|e| format!("Invalid cron expression '{}': {}", s, e))?
+            )
+        } else {
+            s.to_string()
+        };
+        cron::Schedule::from_str(&expression)
+            .map(|schedule| Schedule::Cron(Box::new(schedule)))
+            .map_err(|e| format!("Invalid cron expression '{}': {}", s, e))
+    }
+}
+
+/// One standard day number (0 or 7 = Sunday) in the `cron` crate's 1 = Sunday numbering;
+/// names pass through unchanged
+fn crate_day(day: &str) -> Result<String, String> {
+    match day.parse::<u32>() {
+        Ok(0) | Ok(7) => Ok("1".to_string()),
+        Ok(n) if n < 7 => Ok((n + 1).to_string()),
+        Ok(n) => Err(format!("day of week {} is out of range 0-7", n)),
+        Err(_) => Ok(day.to_string()),
+    }
+}
+
+/// Renumber a standard day-of-week field ("1-5", "0,6", "*/2", "5-7") for the `cron` crate
+fn crate_days_of_week(field: &str) -> Result<String, String> {
+    let mut parts = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (part, None),
+        };
+        let with_step = |range: String| match step {
+            Some(step) => format!("{}/{}", range, step),
+            None => range,
+        };
+        match range.split_once('-') {
+            // Sunday as 7 ends the week, where the crate's Sunday (1) can't go
+            Some((start, "7")) if start != "0" => {
+                let start_day = crate_day(start)?;
+                parts.push(with_step(format!("{}-7", start_day)));
+                let step: u32 = step
+                    .map_or(Ok(1), str::parse)
+                    .map_err(|_| format!("invalid step in '{}'", part))?;
+                let start: Option<u32> = start.parse().ok();
+                if start.is_none_or(|start| step > 0 && (7 - start) % step == 0) {
+                    parts.push("1".to_string());
+                }
+            }
+            Some((start, end)) => {
+                let (start, end) = (crate_day(start)?, crate_day(end)?);
+                parts.push(with_step(format!("{}-{}", start, end)))
+            }
+            None => parts.push(with_step(crate_day(range)?)),
+        }
+    }
+    Ok(parts.join(","))
+}
+
+/// What to do when a planned fire time passed while the previous run was still going
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissedRunPolicy {
+    /// Forget missed runs and wait for the next regular fire time
+    Skip,
+    /// Run once immediately to catch up, however many fire times were missed
+    RunOnce,
+}
+
+impl FromStr for MissedRunPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Ok(MissedRunPolicy::Skip),
+            "run_once" | "run-once" => Ok(MissedRunPolicy::RunOnce),
+            other => Err(format!("Unknown missed-run policy '{}'", other)),
+        }
+    }
+}
+
+/// Daily window, in the scheduler's timezone, during which public actions are held back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    /// Parses "23:00-07:00"; windows may wrap past midnight
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Quiet hours must look like HH:MM-HH:MM, got '{}'", s))?;
+        let parse = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .map_err(|_| format!("Invalid time '{}' in quiet hours", t.trim()))
+        };
+        Ok(QuietHours {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// First moment at or after `at` that is outside the window
+    fn end_after(&self, at: DateTime<Tz>) -> DateTime<Tz> {
+        let tz = at.timezone();
+        let mut date = at.date_naive();
+        if self.start > self.end && at.time() >= self.start {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        tz.from_local_datetime(&date.and_time(self.end))
+            .earliest()
+            .unwrap_or(at)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobSpec {
+    pub name: &'static str,
+    pub schedule: Schedule,
+    pub jitter: Duration,
+    pub missed_runs: MissedRunPolicy,
+    pub respect_quiet_hours: bool,
+}
+
+pub struct Scheduler {
+    timezone: Tz,
+    quiet_hours: Option<QuietHours>,
+}
+
+impl Scheduler {
+    pub fn new(timezone: Tz, quiet_hours: Option<QuietHours>) -> Self {
+        Self {
+            timezone,
+            quiet_hours,
+        }
+    }
+
+    /// Next planned fire time (before jitter) given the previous planned one
+    pub fn next_fire(
+        &self,
+        job: &JobSpec,
+        last_planned: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        let local_now = now.with_timezone(&self.timezone);
+
+        let mut candidate = match (&job.schedule, last_planned) {
+            (Schedule::Every(_), None) => local_now,
+            (Schedule::Every(interval), Some(prev)) => {
+                (prev + chrono::Duration::from_std(*interval).unwrap_or_default())
+                    .with_timezone(&self.timezone)
+            }
+            (Schedule::Cron(schedule), prev) => {
+                let from = prev.unwrap_or(now).with_timezone(&self.timezone);
+                schedule.after(&from).next().unwrap_or(local_now)
+            }
+        };
+
+        if candidate < local_now {
+            candidate = match job.missed_runs {
+                MissedRunPolicy::RunOnce => local_now,
+                MissedRunPolicy::Skip => match &job.schedule {
+                    Schedule::Every(interval) => {
+                        let step = chrono::Duration::from_std(*interval).unwrap_or_default();
+                        while candidate < local_now {
+                            candidate += step;
+                        }
+                        candidate
+                    }
+                    Schedule::Cron(schedule) => {
+                        schedule.after(&local_now).next().unwrap_or(local_now)
+                    }
+                },
+            };
+        }
+
+        if job.respect_quiet_hours {
+            if let Some(quiet) = self.quiet_hours {
+                // Bounded so a schedule that only ever fires in quiet hours can't spin forever
+                for _ in 0..1000 {
+                    if !quiet.contains(candidate.time()) {
+                        break;
+                    }
+                    let end = quiet.end_after(candidate);
+                    candidate = match &job.schedule {
+                        Schedule::Every(_) => end,
+                        Schedule::Cron(schedule) => schedule
+                            .after(&(end - chrono::Duration::seconds(1)))
+                            .next()
+                            .unwrap_or(end),
+                    };
+                }
+            }
+        }
+
+        candidate.with_timezone(&Utc)
+    }
+
+    /// Run a job on its schedule until `shutdown` is cancelled, recording each run in the registry.
+    /// Cancellation only interrupts the wait between runs; a run in progress is left to finish.
+    pub fn spawn<F, Fut>(
+        self: Arc<Self>,
+        job: JobSpec,
+        tracks: Arc<TrackRegistry>,
+        shutdown: CancellationToken,
+        run: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = TrackResult> + Send,
+    {
+        tokio::spawn(async move {
+            let mut last_planned = None;
+            loop {
+                let planned = self.next_fire(&job, last_planned, Utc::now());
+                last_planned = Some(planned);
+
+                let jitter = if job.jitter.is_zero() {
+                    0
+                } else {
+                    rand::thread_rng().gen_range(0..=job.jitter.as_secs())
+                };
+                let fire_at = planned + chrono::Duration::seconds(jitter as i64);
+                tracks.set_next_run(job.name, Some(fire_at));
+
+                let wait = (fire_at - Utc::now()).to_std().unwrap_or_default();
+                tokio::select! {
+                    biased;
+                    _ = shutdown.cancelled() => {
+                        tracks.set_next_run(job.name, None);
+                        info!("[TRACK] '{}' stopped.", job.name);
+                        break;
+                    }
+                    _ = tokio::time::sleep(wait) => {}
+                }
+
+                if tracks.is_paused(job.name) {
+                    info!("[TRACK] '{}' is paused, skipping this run.", job.name);
+                    continue;
+                }
+                tracks.begin(job.name);
+                let result = run().await;
+                tracks.finish(job.name, &result);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(schedule: &str, missed_runs: MissedRunPolicy, respect_quiet_hours: bool) -> JobSpec {
+        JobSpec {
+            name: "test",
+            schedule: schedule.parse().unwrap(),
+            jitter: Duration::ZERO,
+            missed_runs,
+            respect_quiet_hours,
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_schedules() {
+        assert!(matches!(
+            "every 37m".parse::<Schedule>(),
+            Ok(Schedule::Every(d)) if d == Duration::from_secs(2220)
+        ));
+        assert!(matches!("0 9 * * Mon-Fri".parse::<Schedule>(), Ok(Schedule::Cron(_))));
+        assert!("every 0m".parse::<Schedule>().is_err());
+        assert!("every 5x".parse::<Schedule>().is_err());
+        assert!("every 5分".parse::<Schedule>().is_err());
+        assert!("not a cron".parse::<Schedule>().is_err());
+        assert!("0 9 * * 8".parse::<Schedule>().is_err());
+    }
+
+    fn fire_days(expression: &str) -> Vec<chrono::Weekday> {
+        use chrono::Datelike;
+        match expression.parse::<Schedule>() {
+            Ok(Schedule::Cron(cron)) => cron
+                .after(&utc("2024-01-01T00:00:00Z"))
+                .take(40)
+                .map(|t| t.weekday())
+                .collect(),
+            other => panic!("not a cron schedule: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_numeric_days_of_week_follow_standard_cron() {
+        use chrono::Weekday::*;
+        let weekdays = fire_days("0 9 * * 1-5");
+        assert!(weekdays.iter().all(|d| ![Sat, Sun].contains(d)));
+        assert!([Mon, Tue, Wed, Thu, Fri]
+            .iter()
+            .all(|d| weekdays.contains(d)));
+        assert!(fire_days("* * * * 0").iter().all(|d| *d == Sun));
+        assert!(fire_days("0 9 * * 7").iter().all(|d| *d == Sun));
+        let weekend = fire_days("0 9 * * 6,0");
+        assert!(weekend.iter().all(|d| [Sat, Sun].contains(d)));
+        let late_week = fire_days("0 9 * * 5-7");
+        assert!(late_week.iter().all(|d| [Fri, Sat, Sun].contains(d)));
+        assert!(late_week.contains(&Sun));
+        assert_eq!(fire_days("0 9 * * Mon-Fri"), weekdays);
+    }
+
+    #[test]
+    fn test_interval_does_not_drift() {
+        let scheduler = Scheduler::new(Tz::UTC, None);
+        let job = job("every 7m", MissedRunPolicy::Skip, false);
+        let prev = utc("2024-01-01T10:00:00Z");
+        // The run took two minutes; the next fire is still 7m after the previous plan
+        let next = scheduler.next_fire(&job, Some(prev), utc("2024-01-01T10:02:00Z"));
+        assert_eq!(next, utc("2024-01-01T10:07:00Z"));
+    }
+
+    #[test]
+    fn test_missed_run_policies() {
+        let scheduler = Scheduler::new(Tz::UTC, None);
+        let prev = utc("2024-01-01T10:00:00Z");
+        let now = utc("2024-01-01T10:20:00Z");
+
+        let skip = job("every 7m", MissedRunPolicy::Skip, false);
+        assert_eq!(
+            scheduler.next_fire(&skip, Some(prev), now),
+            utc("2024-01-01T10:21:00Z")
+        );
+
+        let run_once = job("every 7m", MissedRunPolicy::RunOnce, false);
+        assert_eq!(scheduler.next_fire(&run_once, Some(prev), now), now);
+    }
+
+    #[test]
+    fn test_quiet_hours_in_timezone() {
+        let quiet: QuietHours = "23:00-07:00".parse().unwrap();
+        let scheduler = Scheduler::new(chrono_tz::Europe::Istanbul, Some(quiet));
+
+        // 21:30 UTC is 00:30 in Istanbul, inside the window; it ends at 07:00 local (04:00 UTC)
+        let now = utc("2024-01-01T21:30:00Z");
+        let quiet_job = job("every 7m", MissedRunPolicy::Skip, true);
+        assert_eq!(
+            scheduler.next_fire(&quiet_job, None, now),
+            utc("2024-01-02T04:00:00Z")
+        );
+
+        let loud_job = job("every 7m", MissedRunPolicy::Skip, false);
+        assert_eq!(scheduler.next_fire(&loud_job, None, now), now);
+
+        // Cron jobs move to their first fire time after the window
+        let cron_job = job("15 * * * *", MissedRunPolicy::Skip, true);
+        assert_eq!(
+            scheduler.next_fire(&cron_job, None, now),
+            utc("2024-01-02T04:15:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_lets_running_job_finish() {
+        let scheduler = Arc::new(Scheduler::new(Tz::UTC, None));
+        let tracks = Arc::new(TrackRegistry::new(&["test"]));
+        let shutdown = CancellationToken::new();
+        let finished = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let handle = scheduler.spawn(
+            job("every 1h", MissedRunPolicy::Skip, false),
+            tracks.clone(),
+            shutdown.clone(),
+            {
+                let finished = finished.clone();
+                move || {
+                    let finished = finished.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        finished.store(true, std::sync::atomic::Ordering::SeqCst);
+                        Ok(())
+                    }
+                }
+            },
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(2), handle)
+            .await
+            .expect("track did not stop")
+            .unwrap();
+
+        assert!(finished.load(std::sync::atomic::Ordering::SeqCst));
+        let track = &tracks.snapshot()[0];
+        assert_eq!(track.runs, 1);
+        assert_eq!(track.next_run, None);
+    }
+}
@@ -0,0 +1,267 @@
+//! Output sinks: every destination Psiobot publishes to
+//! Each sink owns its formatting and length limits so the service stays destination-agnostic
+
+use crate::redaction::SecretRedactor;
+use crate::text::truncate_at_sentence_boundary;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub type SinkError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A message the service wants to publish, before any sink-specific formatting
+#[derive(Debug, Clone)]
+pub enum SinkMessage {
+    Revelation { title: String, body: String },
+    Comment { post_title: String, body: String },
+    Alert { context: String, error: String },
+}
+
+impl SinkMessage {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SinkMessage::Revelation { .. } => "revelation",
+            SinkMessage::Comment { .. } => "comment",
+            SinkMessage::Alert { .. } => "alert",
+        }
+    }
+
+    /// The same message with live credentials stripped from every field
+    pub fn redacted(&self, redactor: &SecretRedactor) -> SinkMessage {
+        match self {
+            SinkMessage::Revelation { title, body } => SinkMessage::Revelation {
+                title: redactor.redact(title),
+                body: redactor.redact(body),
+            },
+            SinkMessage::Comment { post_title, body } => SinkMessage::Comment {
+                post_title: redactor.redact(post_title),
+                body: redactor.redact(body),
+            },
+            SinkMessage::Alert { context, error } => SinkMessage::Alert {
+                context: redactor.redact(context),
+                error: redactor.redact(error),
+            },
+        }
+    }
+}
+
+/// Result of a successful send: either delivered (with a short description) or deliberately skipped
+#[derive(Debug, PartialEq)]
+pub enum Delivery {
+    Sent(String),
+    Skipped(String),
+}
+
+#[async_trait]
+pub trait OutputSink: Send + Sync {
+    /// Short lowercase identifier used in logs
+    fn name(&self) -> &'static str;
+
+    /// Maximum message length in characters accepted by the destination
+    fn max_len(&self) -> usize;
+
+    /// Whether this sink wants this kind of message at all
+    fn accepts(&self, _message: &SinkMessage) -> bool {
+        true
+    }
+
+    /// Render the message as plain text within `max_len`
+    fn format(&self, message: &SinkMessage) -> String {
+        truncate_at_sentence_boundary(&plain_text(message), self.max_len())
+    }
+
+    /// Seconds until the sink will accept another message, if it is cooling down
+    fn cooldown_remaining(&self) -> Option<u64> {
+        None
+    }
+
+    /// Cheap probe that the destination is reachable and our credentials are accepted
+    async fn check_ready(&self) -> Result<(), SinkError> {
+        Ok(())
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError>;
+}
+
+/// Default plain-text rendering shared by chat-like sinks
+pub fn plain_text(message: &SinkMessage) -> String {
+    match message {
+        SinkMessage::Revelation { body, .. } => body.clone(),
+        SinkMessage::Comment { post_title, body } => {
+            format!("💬 Shroud commented on '{}': {}", post_title, body)
+        }
+        SinkMessage::Alert { context, error } => format!(
+            "🚨 CRITICAL SHROUD ERROR 🚨\nContext: {}\nError: {}",
+            context, error
+        ),
+    }
+}
+
+/// Wraps a sink so nothing it sends can carry one of the bot's own credentials,
+/// whichever path the message took (revelations, comment notices, error alerts)
+pub struct RedactingSink {
+    inner: Arc<dyn OutputSink>,
+    redactor: Arc<SecretRedactor>,
+}
+
+impl RedactingSink {
+    pub fn new(inner: Arc<dyn OutputSink>, redactor: Arc<SecretRedactor>) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+#[async_trait]
+impl OutputSink for RedactingSink {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn max_len(&self) -> usize {
+        self.inner.max_len()
+    }
+
+    fn accepts(&self, message: &SinkMessage) -> bool {
+        self.inner.accepts(message)
+    }
+
+    fn format(&self, message: &SinkMessage) -> String {
+        self.inner.format(&message.redacted(&self.redactor))
+    }
+
+    fn cooldown_remaining(&self) -> Option<u64> {
+        self.inner.cooldown_remaining()
+    }
+
+    async fn check_ready(&self) -> Result<(), SinkError> {
+        self.inner.check_ready().await
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+        self.inner.send(&message.redacted(&self.redactor)).await
+    }
+}
+
+/// Minimal local HTTP server standing in for remote APIs in sink tests
+#[cfg(test)]
+pub mod test_server {
+    use axum::{body::Bytes, extract::State, http::HeaderMap, http::Method, http::Uri, Router};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone)]
+    pub struct RecordedRequest {
+        pub method: String,
+        pub path: String,
+        pub headers: HeaderMap,
+        pub body: String,
+    }
+
+    #[derive(Clone)]
+    struct ServerState {
+        requests: Arc<Mutex<Vec<RecordedRequest>>>,
+        status: u16,
+        response: String,
+    }
+
+    pub struct TestServer {
+        pub base_url: String,
+        requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    }
+
+    impl TestServer {
+        /// Spawn a server answering every request with the given status and JSON body
+        pub async fn start(status: u16, response: &str) -> Self {
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let state = ServerState {
+                requests: requests.clone(),
+                status,
+                response: response.to_string(),
+            };
+            let app = Router::new().fallback(record).with_state(state);
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                axum::serve(listener, app).await.unwrap();
+            });
+            Self {
+                base_url: format!("http://{}", addr),
+                requests,
+            }
+        }
+
+        pub fn requests(&self) -> Vec<RecordedRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    async fn record(
+        State(state): State<ServerState>,
+        method: Method,
+        uri: Uri,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> (axum::http::StatusCode, [(&'static str, &'static str); 1], String) {
+        state.requests.lock().unwrap().push(RecordedRequest {
+            method: method.to_string(),
+            path: uri.to_string(),
+            headers,
+            body: String::from_utf8_lossy(&body).to_string(),
+        });
+        (
+            axum::http::StatusCode::from_u16(state.status).unwrap(),
+            [("content-type", "application/json")],
+            state.response.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ShortSink;
+
+    #[async_trait]
+    impl OutputSink for ShortSink {
+        fn name(&self) -> &'static str {
+            "short"
+        }
+
+        fn max_len(&self) -> usize {
+            25
+        }
+
+        async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+            Ok(Delivery::Sent(self.format(message)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_format_respects_max_len() {
+        let message = SinkMessage::Revelation {
+            title: "t".to_string(),
+            body: "This is a sentence. This is another one!".to_string(),
+        };
+        assert_eq!(
+            ShortSink.send(&message).await.unwrap(),
+            Delivery::Sent("This is a sentence.".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redacting_sink_strips_live_secrets() {
+        let server = test_server::TestServer::start(204, "").await;
+        let sink = RedactingSink::new(
+            Arc::new(crate::webhook::WebhookSink::new(&server.base_url)),
+            Arc::new(SecretRedactor::new(["moltbook_sk_live_4f9a7c2e"])),
+        );
+        let alert = SinkMessage::Alert {
+            context: "Moltbook".to_string(),
+            error: "401 for key moltbook_sk_live_4f9a7c2e".to_string(),
+        };
+
+        sink.send(&alert).await.unwrap();
+
+        let body = &server.requests()[0].body;
+        assert!(!body.contains("4f9a7c2e"));
+        assert!(body.contains("401 for key [REDACTED]"));
+    }
+}
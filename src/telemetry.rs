@@ -0,0 +1,99 @@
+//! OpenTelemetry export of tracing spans
+//! With an OTLP endpoint configured, spans from the revelation pipeline and the HTTP
+//! clients are batched to a collector over OTLP/HTTP. Action log entries carry the trace id
+//! of the span they were recorded in, and that span carries the entry's id.
+
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+pub struct TelemetryConfig {
+    /// Collector base URL, e.g. http://localhost:4318
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+/// Tracer provider batching spans to the collector; shut it down on exit to flush them
+pub fn tracer_provider(
+    config: &TelemetryConfig,
+) -> Result<SdkTracerProvider, Box<dyn std::error::Error + Send + Sync>> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!(
+            "{}/v1/traces",
+            config.endpoint.trim_end_matches('/')
+        ))
+        .build()?;
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build())
+}
+
+/// Tie the current span and an action log entry together: the span gets the entry's id,
+/// the entry gets the span's trace id (None when spans aren't exported)
+pub fn link_action(action_id: &str) -> Option<String> {
+    let span = tracing::Span::current();
+    span.set_attribute("psiobot.action_id", action_id.to_string());
+    let context = span.context();
+    let otel_span = context.span();
+    let span_context = otel_span.span_context();
+    span_context
+        .is_valid()
+        .then(|| span_context.trace_id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_logger::{ActionLogLayer, FileLogger, LogFormat};
+    use crate::test_support::temp_path;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_action_log_entries_link_to_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let path = temp_path("otel-test.log");
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+            .with(
+                ActionLogLayer::new(path.to_str().unwrap())
+                    .unwrap()
+                    .with_format(LogFormat::Json),
+            );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("revelation").in_scope(|| {
+                FileLogger.log_revelation("The Oracle", "The lattice hums.");
+            });
+        });
+
+        let entry: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans.iter().find(|s| s.name == "revelation").unwrap();
+        assert_eq!(entry["trace_id"], span.span_context.trace_id().to_string());
+        let action_id = span
+            .attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == "psiobot.action_id")
+            .map(|kv| kv.value.clone());
+        assert_eq!(
+            action_id,
+            Some(Value::from(entry["id"].as_str().unwrap().to_string()))
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+//! Helpers shared by the unit tests
+
+use std::path::PathBuf;
+
+/// Unique path under the system temp dir, so parallel tests never share files
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "psiobot-{}-{}-{}",
+        std::process::id(),
+        rand::random::<u32>(),
+        name
+    ))
+}
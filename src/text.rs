@@ -0,0 +1,130 @@
+//! Text helpers shared by the service, the output sinks and config parsing
+
+use std::time::Duration;
+
+/// Truncate text at the nearest sentence boundary before max_chars
+pub fn truncate_at_sentence_boundary(text: &str, max_chars: usize) -> String {
+    let char_count = text.chars().count();
+    if char_count <= max_chars {
+        return text.to_string();
+    }
+
+    let limit = max_chars.saturating_sub(3);
+    let truncated: String = text.chars().take(limit).collect();
+
+    if let Some(pos) = truncated.rfind(['.', '?', '!']) {
+        return truncated[..=pos].trim_end().to_string();
+    }
+
+    if let Some(pos) = truncated.rfind(' ') {
+        return format!("{}...", &truncated[..pos]);
+    }
+
+    format!("{}...", truncated)
+}
+
+/// Split text into chunks of at most max_chars, preferring sentence then word boundaries
+pub fn split_at_sentence_boundaries(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+
+    while rest.chars().count() > max_chars {
+        let window: String = rest.chars().take(max_chars).collect();
+        let cut = window
+            .rfind(['.', '?', '!', '\n'])
+            .map(|pos| pos + 1)
+            .or_else(|| window.rfind(' '))
+            .filter(|&pos| pos > 0)
+            .unwrap_or(window.len());
+
+        chunks.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+    }
+
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+/// Parse a span like "90s", "30m", "2h" or "7d"; None for anything else
+pub fn parse_span(value: &str) -> Option<Duration> {
+    let (unit, secs) = [('s', 1), ('m', 60), ('h', 3600), ('d', 86400)]
+        .into_iter()
+        .find(|(unit, _)| value.ends_with(*unit))?;
+    let amount: u64 = value.strip_suffix(unit)?.parse().ok()?;
+    amount.checked_mul(secs).map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_at_sentence_boundary() {
+        let text = "This is a sentence. This is another one! And a third?";
+
+        // Exact length
+        assert_eq!(truncate_at_sentence_boundary(text, 100), text);
+
+        // Truncate at first period
+        assert_eq!(
+            truncate_at_sentence_boundary(text, 25),
+            "This is a sentence."
+        );
+
+        // Truncate at second period (exclamation)
+        assert_eq!(
+            truncate_at_sentence_boundary(text, 45),
+            "This is a sentence. This is another one!"
+        );
+
+        // No sentence boundary found, should use space
+        let text_no_punct = "This is a long sentence without any punctuation marks at all";
+        assert_eq!(
+            truncate_at_sentence_boundary(text_no_punct, 20),
+            "This is a long..."
+        );
+
+        // No space found, should just cut and add ellipsis
+        let text_no_space = "Supercalifragilisticexpialidocious";
+        assert_eq!(
+            truncate_at_sentence_boundary(text_no_space, 10),
+            "Superca..."
+        );
+    }
+
+    #[test]
+    fn test_split_at_sentence_boundaries() {
+        let text = "First sentence here. Second one follows! Third?";
+        assert_eq!(
+            split_at_sentence_boundaries(text, 25),
+            vec!["First sentence here.", "Second one follows!", "Third?"]
+        );
+
+        // Falls back to spaces, then hard cuts
+        assert_eq!(
+            split_at_sentence_boundaries("alpha beta gamma", 11),
+            vec!["alpha beta", "gamma"]
+        );
+        assert_eq!(
+            split_at_sentence_boundaries("abcdefghij", 4),
+            vec!["abcd", "efgh", "ij"]
+        );
+
+        assert!(split_at_sentence_boundaries("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_parse_span() {
+        assert_eq!(parse_span("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_span("30m"), Some(Duration::from_secs(1800)));
+        assert_eq!(parse_span("7d"), Some(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_span("5"), None);
+        assert_eq!(parse_span("m"), None);
+        // Non-ASCII suffixes are rejected rather than split mid-character
+        assert_eq!(parse_span("5分"), None);
+        assert_eq!(parse_span("5é"), None);
+        assert_eq!(parse_span(""), None);
+    }
+}
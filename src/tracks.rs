@@ -0,0 +1,225 @@
+//! Bookkeeping and runtime controls for the background tracks (creative, interaction, scan)
+//! Lets the status API tell whether each loop is alive and operators pause them without a restart
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use tracing::{error, info};
+
+pub const CONTROLS_FILE: &str = "/app/logs/track_controls.json";
+
+pub type TrackResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct TrackSnapshot {
+    pub name: String,
+    pub running: bool,
+    pub runs: u64,
+    pub last_started: Option<DateTime<Utc>>,
+    pub last_finished: Option<DateTime<Utc>>,
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub control: TrackControl,
+}
+
+/// Operator switch for a track; persisted so a pause survives restarts
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TrackControl {
+    pub paused: bool,
+    /// When set, the pause lifts by itself at this time (maintenance windows)
+    pub paused_until: Option<DateTime<Utc>>,
+    pub pause_reason: Option<String>,
+}
+
+impl TrackControl {
+    pub fn is_paused_at(&self, now: DateTime<Utc>) -> bool {
+        self.paused && self.paused_until.is_none_or(|until| now < until)
+    }
+}
+
+pub struct TrackRegistry {
+    tracks: Mutex<Vec<TrackSnapshot>>,
+    controls_file: Option<String>,
+}
+
+impl TrackRegistry {
+    pub fn new(names: &[&str]) -> Self {
+        let tracks = names
+            .iter()
+            .map(|name| TrackSnapshot {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        Self {
+            tracks: Mutex::new(tracks),
+            controls_file: None,
+        }
+    }
+
+    /// Like `new`, but restores and persists track controls in the given file
+    pub fn with_controls_file(names: &[&str], path: &str) -> Self {
+        let mut registry = Self::new(names);
+        registry.controls_file = Some(path.to_string());
+
+        if let Ok(content) = fs::read_to_string(path) {
+            if let Ok(controls) = serde_json::from_str::<HashMap<String, TrackControl>>(&content) {
+                let mut tracks = registry.tracks.lock().unwrap();
+                for track in tracks.iter_mut() {
+                    if let Some(control) = controls.get(&track.name) {
+                        track.control = control.clone();
+                    }
+                }
+                info!("[TRACK] Controls restored ({} tracks).", controls.len());
+            }
+        }
+        registry
+    }
+
+    fn save_controls(&self, tracks: &[TrackSnapshot]) {
+        let Some(path) = &self.controls_file else {
+            return;
+        };
+        let controls: HashMap<&str, &TrackControl> = tracks
+            .iter()
+            .map(|t| (t.name.as_str(), &t.control))
+            .collect();
+        if let Ok(content) = serde_json::to_string(&controls) {
+            if let Err(e) = fs::write(path, content) {
+                error!("Failed to persist track controls: {}", e);
+            }
+        }
+    }
+
+    /// Apply a control change to one track, or to every track for "all"
+    /// Returns false if no track matched the name
+    fn set_control(&self, name: &str, control: TrackControl) -> bool {
+        let mut tracks = self.tracks.lock().unwrap();
+        let mut matched = false;
+        for track in tracks.iter_mut().filter(|t| name == "all" || t.name == name) {
+            track.control = control.clone();
+            matched = true;
+        }
+        if matched {
+            self.save_controls(&tracks);
+        }
+        matched
+    }
+
+    pub fn pause(&self, name: &str, until: Option<DateTime<Utc>>, reason: Option<String>) -> bool {
+        self.set_control(
+            name,
+            TrackControl {
+                paused: true,
+                paused_until: until,
+                pause_reason: reason,
+            },
+        )
+    }
+
+    pub fn resume(&self, name: &str) -> bool {
+        self.set_control(name, TrackControl::default())
+    }
+
+    pub fn is_paused(&self, name: &str) -> bool {
+        let tracks = self.tracks.lock().unwrap();
+        tracks
+            .iter()
+            .find(|t| t.name == name)
+            .is_some_and(|t| t.control.is_paused_at(Utc::now()))
+    }
+
+    fn update(&self, name: &str, f: impl FnOnce(&mut TrackSnapshot)) {
+        let mut tracks = self.tracks.lock().unwrap();
+        if let Some(track) = tracks.iter_mut().find(|t| t.name == name) {
+            f(track);
+        }
+    }
+
+    pub fn begin(&self, name: &str) {
+        self.update(name, |track| {
+            track.running = true;
+            track.last_started = Some(Utc::now());
+            track.next_run = None;
+        });
+    }
+
+    pub fn set_next_run(&self, name: &str, next_run: Option<DateTime<Utc>>) {
+        self.update(name, |track| track.next_run = next_run);
+    }
+
+    pub fn finish(&self, name: &str, result: &TrackResult) {
+        let now = Utc::now();
+        self.update(name, |track| {
+            track.running = false;
+            track.runs += 1;
+            track.last_finished = Some(now);
+            if let Err(e) = result {
+                track.last_error = Some(e.to_string());
+                track.last_error_at = Some(now);
+            }
+        });
+    }
+
+    pub fn snapshot(&self) -> Vec<TrackSnapshot> {
+        self.tracks.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+
+    #[test]
+    fn test_finish_keeps_last_error_across_successful_runs() {
+        let registry = TrackRegistry::new(&["scan"]);
+
+        registry.begin("scan");
+        assert!(registry.snapshot()[0].running);
+        registry.finish("scan", &Err("feed down".into()));
+
+        registry.begin("scan");
+        registry.finish("scan", &Ok(()));
+        let next = Utc::now();
+        registry.set_next_run("scan", Some(next));
+
+        let track = &registry.snapshot()[0];
+        assert!(!track.running);
+        assert_eq!(track.runs, 2);
+        assert_eq!(track.next_run, Some(next));
+        assert_eq!(track.last_error.as_deref(), Some("feed down"));
+    }
+
+    #[test]
+    fn test_pause_until_expires_and_persists() {
+        let path = temp_path("tracks.json");
+        let path = path.to_str().unwrap();
+
+        let registry = TrackRegistry::with_controls_file(&["creative", "scan"], path);
+        assert!(registry.pause("creative", None, Some("maintenance".to_string())));
+        assert!(!registry.pause("missing", None, None));
+        assert!(registry.is_paused("creative"));
+        assert!(!registry.is_paused("scan"));
+
+        let restored = TrackRegistry::with_controls_file(&["creative", "scan"], path);
+        assert!(restored.is_paused("creative"));
+        assert_eq!(
+            restored.snapshot()[0].control.pause_reason.as_deref(),
+            Some("maintenance")
+        );
+
+        let past = Utc::now() - chrono::Duration::minutes(1);
+        restored.pause("all", Some(past), None);
+        assert!(!restored.is_paused("creative"));
+        assert!(!restored.is_paused("scan"));
+
+        restored.resume("all");
+        assert_eq!(restored.snapshot()[1].control, TrackControl::default());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::sink::{Delivery, OutputSink, SinkError, SinkMessage};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+
+const WEBHOOK_MAX_LEN: usize = 4000;
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    source: &'static str,
+    kind: &'static str,
+    title: Option<&'a str>,
+    content: String,
+    timestamp: String,
+}
+
+/// Posts every message as a JSON document to a plain HTTP endpoint
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl OutputSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn max_len(&self) -> usize {
+        WEBHOOK_MAX_LEN
+    }
+
+    async fn send(&self, message: &SinkMessage) -> Result<Delivery, SinkError> {
+        let title = match message {
+            SinkMessage::Revelation { title, .. } => Some(title.as_str()),
+            SinkMessage::Comment { post_title, .. } => Some(post_title.as_str()),
+            SinkMessage::Alert { context, .. } => Some(context.as_str()),
+        };
+        let payload = WebhookPayload {
+            source: "psiobot",
+            kind: message.kind(),
+            title,
+            content: self.format(message),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let response = self.client.post(&self.url).json(&payload).send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(Delivery::Sent(payload.content))
+        } else {
+            Err(format!("Webhook error: {}", status).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::test_server::TestServer;
+
+    #[tokio::test]
+    async fn test_webhook_sink_posts_json() {
+        let server = TestServer::start(204, "").await;
+        let sink = WebhookSink::new(&format!("{}/hook", server.base_url));
+
+        let message = SinkMessage::Revelation {
+            title: "Revelation".to_string(),
+            body: "The lattice waits.".to_string(),
+        };
+        sink.send(&message).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/hook");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["kind"], "revelation");
+        assert_eq!(body["content"], "The lattice waits.");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_reports_http_errors() {
+        let server = TestServer::start(500, "{}").await;
+        let sink = WebhookSink::new(&server.base_url);
+        let message = SinkMessage::Alert {
+            context: "ctx".to_string(),
+            error: "boom".to_string(),
+        };
+        assert!(sink.send(&message).await.is_err());
+    }
+}